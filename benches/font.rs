@@ -0,0 +1,73 @@
+#![feature(test)]
+extern crate test;
+use test::Bencher;
+
+extern crate radiant_rs;
+use radiant_rs::*;
+
+const TEXT_ITER: u32 = 100;
+
+#[bench]
+fn font_writing(b: &mut Bencher) {
+
+    let display = Display::builder().hidden().build().unwrap();
+    let _renderer = Renderer::new(&display).unwrap();
+    let font = Font::builder(display.context()).family("Arial").size(16.0).build().unwrap();
+    let layer = Layer::new((640., 480.));
+
+    display.clear_frame(Color::BLACK);
+
+    // make sure layer is full allocated and glyphs are cached
+    for i in 0..TEXT_ITER {
+        font.write(&layer, &format!("Score: {:08}  Lives: {}  FPS: {}", i * 250, i % 5, 60), (10., 10.), Color::WHITE);
+    }
+
+    b.iter(|| {
+        layer.clear();
+        for i in 0..TEXT_ITER {
+            font.write(&layer, &format!("Score: {:08}  Lives: {}  FPS: {}", i * 250, i % 5, 60), (10., 10.), Color::WHITE);
+        }
+    });
+
+    display.swap_frame();
+}
+
+#[bench]
+fn font_wrapped_writing(b: &mut Bencher) {
+
+    let display = Display::builder().hidden().build().unwrap();
+    let _renderer = Renderer::new(&display).unwrap();
+    let font = Font::builder(display.context()).family("Arial").size(12.0).build().unwrap();
+    let layer = Layer::new((640., 480.));
+    let text = include_str!("../examples/res/limerick.txt");
+
+    display.clear_frame(Color::BLACK);
+
+    // make sure layer is full allocated and glyphs are cached
+    for _ in 0..TEXT_ITER {
+        font.write_wrapped(&layer, text, (10., 10.), Color::WHITE, 200.);
+    }
+
+    b.iter(|| {
+        layer.clear();
+        for _ in 0..TEXT_ITER {
+            font.write_wrapped(&layer, text, (10., 10.), Color::WHITE, 200.);
+        }
+    });
+
+    display.swap_frame();
+}
+
+#[bench]
+fn font_measuring(b: &mut Bencher) {
+
+    let display = Display::builder().hidden().build().unwrap();
+    let font = Font::builder(display.context()).family("Arial").size(12.0).build().unwrap();
+    let text = include_str!("../examples/res/limerick.txt");
+
+    b.iter(|| {
+        for _ in 0..TEXT_ITER {
+            test::black_box(font.measure(text, 200.));
+        }
+    });
+}
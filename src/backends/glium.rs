@@ -1,5 +1,6 @@
 use prelude::*;
 use core;
+use image;
 use glium;
 use glium::uniforms::Uniforms;
 use glium::{glutin, Surface};
@@ -88,6 +89,7 @@ pub mod public {
             frame       : Rc::new(RefCell::new(None)),
             input_data  : Arc::new(RwLock::new(core::InputData::new())),
             fullscreen  : Rc::new(RefCell::new(None)), // TODO: fullscreen state unknown, doesn't appear to be possible to retrieve from winit
+            recorder    : Rc::new(RefCell::new(None)),
         }
     }
 
@@ -677,6 +679,19 @@ impl Texture2d {
         let (glium_src_rect, glium_target_rect) = blit_coords(source_rect, source_height, target_rect, target_height);
         src_frame.0.blit_color(&glium_src_rect, &self.0.as_surface(), &glium_target_rect, magnify_filter(filter));
     }
+    pub fn read(self: &Self, rect: core::Rect<u32>) -> image::RgbaImage {
+        let ((left, top), (width, height)) = rect;
+        let source = self.0.main_level().first_layer().into_image(None).unwrap();
+        let raw: glium::texture::RawImage2d<u8> = source.raw_read(&glium::Rect {
+            left: left,
+            bottom: self.0.height() - top - height,
+            width: width,
+            height: height,
+        });
+        let image = image::RgbaImage::from_raw(raw.width, raw.height, raw.data.into_owned()).unwrap();
+        // glium returns the bottom row first
+        image::imageops::flip_vertical(&image)
+    }
 
     /// Converts TextureFormat to the supported gliums texture formats
     fn convert_format(format: core::TextureFormat) -> glium::texture::UncompressedFloatFormat {
@@ -801,6 +816,21 @@ impl Context {
         }
     }
 
+    /// Returns the maximum texture width and height supported by the OpenGL implementation.
+    pub fn max_texture_size(self: &Self) -> u32 {
+        // glium does not expose GL_MAX_TEXTURE_SIZE, query it directly
+        use self::glutin::GlContext;
+        const GL_MAX_TEXTURE_SIZE: u32 = 0x0D33;
+        let get_integerv = self.display.gl_window().get_proc_address("glGetIntegerv");
+        if get_integerv.is_null() {
+            return u32::max_value();
+        }
+        let get_integerv: extern "system" fn(u32, *mut i32) = unsafe { mem::transmute(get_integerv) };
+        let mut size = 0;
+        unsafe { self.display.exec_in_context(|| get_integerv(GL_MAX_TEXTURE_SIZE, &mut size)) };
+        if size > 0 { size as u32 } else { u32::max_value() }
+    }
+
     /// Creates an index buffer for rectangles consisting of two triangles.
     fn create_index_buffer(display: &glium::Display, max_sprites: usize) -> glium::IndexBuffer<u32> {
 
@@ -850,7 +880,7 @@ impl Context {
     }
 
     /// Draw given vertices.
-    fn draw<'b, 'a: 'b>(self: &'a mut Self, target: &core::RenderTarget, vertices: &[core::Vertex], dirty: bool, buffer_hint: usize, program: &Program, /*mut*/ uniforms: GliumUniformList<'b>, blendmode: &core::BlendMode) {
+    fn draw<'b, 'a: 'b>(self: &'a mut Self, target: &core::RenderTarget, vertices: &[core::Vertex], dirty: Option<(usize, usize)>, buffer_hint: usize, program: &Program, /*mut*/ uniforms: GliumUniformList<'b>, blendmode: &core::BlendMode) {
 
         let num_vertices = vertices.len();
         let num_sprites = num_vertices / 4;
@@ -863,9 +893,14 @@ impl Context {
 
         let (vb_index, vb_dirty) = self.select_vertex_buffer(buffer_hint, num_vertices);
         {
-            if dirty || vb_dirty {
-                let vb_slice = self.vertex_buffers[vb_index].buffer.slice(0 .. num_vertices).unwrap();
-                vb_slice.write(&vertices[0 .. num_vertices]);
+            // upload everything into fresh buffers, otherwise only the dirty range
+            let range = if vb_dirty { Some((0, num_vertices)) } else { dirty };
+            if let Some((start, end)) = range {
+                let end = end.min(num_vertices);
+                if start < end {
+                    let vb_slice = self.vertex_buffers[vb_index].buffer.slice(start .. end).unwrap();
+                    vb_slice.write(&vertices[start .. end]);
+                }
             }
         }
 
@@ -1020,7 +1055,7 @@ impl<'b> Uniforms for GliumUniformList<'b> {
 // Drawing
 // --------------
 
-pub fn draw_layer(target: &core::RenderTarget, program: &core::Program, context: &mut core::ContextData, layer: &core::Layer, component: u32) {
+pub fn draw_layer(target: &core::RenderTarget, program: &core::Program, context: &mut core::ContextData, layer: &core::Layer, component: u32, texture: Option<&core::Texture>) {
 
     use self::glium::uniforms::{MagnifySamplerFilter, SamplerWrapFunction};
 
@@ -1031,16 +1066,22 @@ pub fn draw_layer(target: &core::RenderTarget, program: &core::Program, context:
         .add("_rd_color", GliumUniform::Vec4(layer.color().deref().into()))
         .add("_rd_tex", GliumUniform::Sampled2d(context.font_texture.as_ref().unwrap().0.sampled().magnify_filter(MagnifySamplerFilter::Nearest).wrap_function(SamplerWrapFunction::Clamp)))
         .add("_rd_comp", GliumUniform::UnsignedInt(component))
-        .add("_rd_tex1", GliumUniform::Texture2dArray(&context.tex_arrays[1].data.0))
-        .add("_rd_tex2", GliumUniform::Texture2dArray(&context.tex_arrays[2].data.0))
-        .add("_rd_tex3", GliumUniform::Texture2dArray(&context.tex_arrays[3].data.0))
-        .add("_rd_tex4", GliumUniform::Texture2dArray(&context.tex_arrays[4].data.0))
-        .add("_rd_tex5", GliumUniform::Texture2dArray(&context.tex_arrays[5].data.0));
+        .add("_rd_atlas", GliumUniform::Texture2dArray(&context.atlas.as_ref().unwrap().data.0));
+
+    for bucket_id in 1..core::NUM_BUCKETS {
+        glium_uniforms.add(core::BUCKET_SAMPLERS[bucket_id], GliumUniform::Texture2dArray(&context.tex_arrays[bucket_id].data.0));
+    }
+
+    if let Some(texture) = texture {
+        glium_uniforms.add("_rd_layer_tex", GliumUniform::Texture2d(&texture.handle.0));
+    }
 
+    // undirty first, retained layers lock their vertices until drawn
+    let dirty = layer.undirty();
     let vertices = layer.vertices();
     let vertices = vertices.deref();
 
-    context.backend_context.as_mut().unwrap().draw(target, vertices, layer.undirty(), layer.id(), &program.sprite_program, glium_uniforms, &layer.blendmode());
+    context.backend_context.as_mut().unwrap().draw(target, vertices, dirty, layer.id(), &program.sprite_program, glium_uniforms, &layer.blendmode());
 }
 
 pub fn draw_rect<T>(target: &core::RenderTarget, program: &core::Program, context: &mut core::ContextData, blend: core::BlendMode, info: core::DrawBuilder<T>, view_matrix: core::Mat4, model_matrix: core::Mat4, color: core::Color, texture: Option<&core::Texture>) {
@@ -1065,7 +1106,7 @@ pub fn draw_rect<T>(target: &core::RenderTarget, program: &core::Program, contex
     let vertices = &context.single_rect;
     let vertices = &vertices[..];
 
-    backend_context.draw(target, vertices, false, 0, &program.texture_program, glium_uniforms, &blend);
+    backend_context.draw(target, vertices, None, 0, &program.texture_program, glium_uniforms, &blend);
 }
 
 // --------------
@@ -13,4 +13,12 @@ mod null;
 #[cfg(feature = "backend-null")]
 pub mod backend {
     pub use super::null::*;
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "backend-software")]
+mod software;
+
+#[cfg(feature = "backend-software")]
+pub mod backend {
+    pub use super::software::*;
+}
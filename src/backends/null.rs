@@ -7,6 +7,7 @@ This sample show the minimum required implementation of a backend.
 
 use core;
 use core::math::*;
+use image;
 
 // --------------
 // Public interface provided to Radiant-API-user in radiant_rs::backend
@@ -149,6 +150,9 @@ impl Texture2d {
     }
     pub fn copy_rect_from_frame(self: &Self, src_frame: &Frame, source_rect: Rect<i32>, target_rect: Rect<i32>, filter: core::TextureFilter) {
     }
+    pub fn read(self: &Self, rect: Rect<u32>) -> image::RgbaImage {
+        image::RgbaImage::new((rect.1).0, (rect.1).1)
+    }
 }
 
 // --------------
@@ -179,7 +183,7 @@ impl Context {
 // Drawing
 // --------------
 
-pub fn draw_layer(target: &core::RenderTarget, program: &core::Program, context: &mut core::ContextData, layer: &core::Layer, component: u32) {
+pub fn draw_layer(target: &core::RenderTarget, program: &core::Program, context: &mut core::ContextData, layer: &core::Layer, component: u32, texture: Option<&core::Texture>) {
 }
 
 pub fn draw_rect(target: &core::RenderTarget, program: &core::Program, context: &mut core::ContextData, blend: core::BlendMode, info: core::DrawRectInfo, view_matrix: Mat4, model_matrix: Mat4, color: core::Color, texture: &core::Texture) {
@@ -0,0 +1,694 @@
+/*!
+Software-Renderer
+
+Rasterizes layers and rectangles into CPU-side framebuffers. Intended for headless environments like
+CI machines without a GPU, where rendering results need to be inspected or compared.
+
+Custom fragment shaders are not executed. Every draw is shaded as if the default program was used.
+*/
+
+use prelude::*;
+use core;
+use image;
+
+// --------------
+// Public interface provided to Radiant-API-user in radiant_rs::backend
+// --------------
+
+pub mod public {
+    use super::core;
+    use image;
+
+    /// Returns the current contents of the given texture.
+    pub fn read_texture(texture: &core::Texture) -> image::RgbaImage {
+        texture.read_pixels()
+    }
+
+    /// Returns the contents of the most recently swapped frame of the given display.
+    pub fn read_display(display: &core::Display) -> image::RgbaImage {
+        let front = display.handle.front.borrow();
+        front.to_image(((0, 0), (front.width, front.height)))
+    }
+}
+
+// --------------
+// Error
+// --------------
+
+#[derive(Debug)]
+pub enum Error {
+    Failed,
+}
+
+// --------------
+// Surface
+// --------------
+
+/// A CPU-side RGBA image. Rows are stored bottom to top to match the texture coordinate space of the other backends.
+#[derive(Clone)]
+struct Surface {
+    width   : u32,
+    height  : u32,
+    clamp   : bool,
+    data    : Vec<[f32; 4]>,
+}
+
+impl Surface {
+
+    /// Creates a new transparent surface.
+    fn new(width: u32, height: u32, clamp: bool) -> Self {
+        Surface {
+            width   : width,
+            height  : height,
+            clamp   : clamp,
+            data    : vec![[0.0; 4]; width as usize * height as usize],
+        }
+    }
+
+    /// Creates a new surface from given raw frame. Single channel frames are replicated to all channels.
+    fn from_raw(raw: &core::RawFrame, clamp: bool) -> Self {
+        let channels = raw.channels as usize;
+        let data = raw.data.chunks(channels).map(|texel| {
+            if channels == 4 {
+                [ texel[0] as f32 / 255.0, texel[1] as f32 / 255.0, texel[2] as f32 / 255.0, texel[3] as f32 / 255.0 ]
+            } else {
+                let value = texel[0] as f32 / 255.0;
+                [ value, value, value, value ]
+            }
+        }).collect();
+        Surface {
+            width   : raw.width,
+            height  : raw.height,
+            clamp   : clamp,
+            data    : data,
+        }
+    }
+
+    /// Sets all pixels to given color.
+    fn clear(self: &mut Self, color: core::Color) {
+        let color = self.limit(color.into());
+        for pixel in self.data.iter_mut() {
+            *pixel = color;
+        }
+    }
+
+    /// Returns the pixel at given coordinates.
+    fn get(self: &Self, x: u32, y: u32) -> [f32; 4] {
+        self.data[(y * self.width + x) as usize]
+    }
+
+    /// Sets the pixel at given coordinates.
+    fn set(self: &mut Self, x: u32, y: u32, color: [f32; 4]) {
+        let color = self.limit(color);
+        self.data[(y * self.width + x) as usize] = color;
+    }
+
+    /// Blends given color into the pixel at given coordinates.
+    fn blend(self: &mut Self, x: u32, y: u32, color: [f32; 4], blendmode: &core::BlendMode) {
+        let destination = self.get(x, y);
+        self.set(x, y, blend(color, destination, blendmode));
+    }
+
+    /// Samples the surface at given texture coordinates.
+    fn sample(self: &Self, uv: [f32; 2], filter: core::TextureFilter, wrap: core::TextureWrap) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [0.0; 4];
+        }
+        let x = uv[0] * self.width as f32;
+        let y = uv[1] * self.height as f32;
+        if filter == core::TextureFilter::Nearest {
+            self.texel(x.floor() as i32, y.floor() as i32, wrap)
+        } else {
+            let x = x - 0.5;
+            let y = y - 0.5;
+            let (x0, y0) = (x.floor() as i32, y.floor() as i32);
+            let (fx, fy) = (x - x.floor(), y - y.floor());
+            let top = mix(self.texel(x0, y0, wrap), self.texel(x0 + 1, y0, wrap), fx);
+            let bottom = mix(self.texel(x0, y0 + 1, wrap), self.texel(x0 + 1, y0 + 1, wrap), fx);
+            mix(top, bottom, fy)
+        }
+    }
+
+    /// Returns the texel at given, possibly out of range, coordinates.
+    fn texel(self: &Self, x: i32, y: i32, wrap: core::TextureWrap) -> [f32; 4] {
+        self.get(wrap_coord(x, self.width, wrap), wrap_coord(y, self.height, wrap))
+    }
+
+    /// Copies a rectangle from the source to a rectangle on this surface. Rectangles are given as
+    /// top/left position and dimensions.
+    fn copy_rect_from(self: &mut Self, source: &Surface, source_rect: core::Rect<i32>, target_rect: core::Rect<i32>, filter: core::TextureFilter) {
+        let ((source_x, source_y), (source_width, source_height)) = source_rect;
+        let ((target_x, target_y), (target_width, target_height)) = target_rect;
+        // convert top/left to the bottom/left row order used by the surface
+        let source_bottom = source.height as i32 - source_height - source_y;
+        let target_bottom = self.height as i32 - target_height - target_y;
+        for y in 0..target_height {
+            for x in 0..target_width {
+                let tx = target_x + x;
+                let ty = target_bottom + y;
+                if tx < 0 || ty < 0 || tx >= self.width as i32 || ty >= self.height as i32 {
+                    continue;
+                }
+                let u = (source_x as f32 + (x as f32 + 0.5) * source_width as f32 / target_width as f32) / source.width as f32;
+                let v = (source_bottom as f32 + (y as f32 + 0.5) * source_height as f32 / target_height as f32) / source.height as f32;
+                let color = source.sample([ u, v ], filter, core::TextureWrap::Clamp);
+                self.set(tx as u32, ty as u32, color);
+            }
+        }
+    }
+
+    /// Copies the entire source onto the entire surface.
+    fn copy_from(self: &mut Self, source: &Surface, filter: core::TextureFilter) {
+        let source_rect = ((0, 0), (source.width as i32, source.height as i32));
+        let target_rect = ((0, 0), (self.width as i32, self.height as i32));
+        self.copy_rect_from(source, source_rect, target_rect, filter);
+    }
+
+    /// Returns the surface as an image with top to bottom row order.
+    fn to_image(self: &Self, rect: core::Rect<u32>) -> image::RgbaImage {
+        let ((left, top), (width, height)) = rect;
+        image::RgbaImage::from_fn(width, height, |x, y| {
+            let pixel = self.get(left + x, self.height - top - y - 1);
+            image::Rgba([ to_u8(pixel[0]), to_u8(pixel[1]), to_u8(pixel[2]), to_u8(pixel[3]) ])
+        })
+    }
+
+    /// Clamps given color to the range supported by the surface.
+    fn limit(self: &Self, color: [f32; 4]) -> [f32; 4] {
+        if self.clamp {
+            [ clamp(color[0]), clamp(color[1]), clamp(color[2]), clamp(color[3]) ]
+        } else {
+            color
+        }
+    }
+}
+
+// --------------
+// Display
+// --------------
+
+#[derive(Clone)]
+pub struct Display {
+    dimensions  : core::Point2<u32>,
+    front       : Rc<RefCell<Surface>>,
+}
+
+impl Display {
+    pub fn new(descriptor: core::DisplayBuilder) -> core::Result<Display> {
+        Ok(Display {
+            dimensions  : (descriptor.width, descriptor.height),
+            front       : Rc::new(RefCell::new(Surface::new(descriptor.width, descriptor.height, true))),
+        })
+    }
+    pub fn draw(self: &Self) -> Frame {
+        Frame {
+            surface : RefCell::new(Surface::new(self.dimensions.0, self.dimensions.1, true)),
+            front   : self.front.clone(),
+        }
+    }
+    pub fn framebuffer_dimensions(self: &Self) -> core::Point2<u32> {
+        self.dimensions
+    }
+    pub fn window_dimensions(self: &Self) -> core::Point2<u32> {
+        self.dimensions
+    }
+    pub fn set_cursor_position(self: &Self, _position: core::Point2<i32>) {
+    }
+    pub fn set_cursor_state(self: &Self, _state: core::CursorState) {
+    }
+    pub fn set_fullscreen(self: &Self, _monitor: Option<core::Monitor>) -> bool {
+        false
+    }
+    pub fn poll_events<F>(self: &Self, _callback: F) where F: FnMut(core::Event) -> () {
+    }
+    pub fn show(self: &Self) {
+    }
+    pub fn hide(self: &Self) {
+    }
+    pub fn set_title(self: &Self, _title: &str) {
+    }
+}
+
+// --------------
+// Frame
+// --------------
+
+pub struct Frame {
+    surface : RefCell<Surface>,
+    front   : Rc<RefCell<Surface>>,
+}
+
+impl Frame {
+
+    /// Clears the frame with the given color.
+    pub fn clear(self: &mut Self, color: core::Color) {
+        self.surface.borrow_mut().clear(color);
+    }
+
+    /// Finishes the frame. Called on swap.
+    pub fn finish(self: Self) {
+        *self.front.borrow_mut() = self.surface.into_inner();
+    }
+
+    /// Copies given texture to given display.
+    pub fn copy_from_texture(self: &Self, source: &core::Texture, filter: core::TextureFilter) {
+        self.surface.borrow_mut().copy_from(&source.handle.0.borrow(), filter);
+    }
+
+    /// Copies the source rectangle to the target rectangle on the given display.
+    pub fn copy_rect(self: &Self, source_rect: core::Rect<i32>, target_rect: core::Rect<i32>, filter: core::TextureFilter) {
+        let source = self.surface.borrow().clone();
+        self.surface.borrow_mut().copy_rect_from(&source, source_rect, target_rect, filter);
+    }
+
+    /// Copies the source rectangle from the given texture to the target rectangle on the given display.
+    pub fn copy_rect_from_texture(self: &Self, source: &core::Texture, source_rect: core::Rect<i32>, target_rect: core::Rect<i32>, filter: core::TextureFilter) {
+        self.surface.borrow_mut().copy_rect_from(&source.handle.0.borrow(), source_rect, target_rect, filter);
+    }
+
+    /// Returns the dimensions of the frame.
+    pub fn dimensions(self: &Self) -> core::Point2<u32> {
+        let surface = self.surface.borrow();
+        (surface.width, surface.height)
+    }
+}
+
+// --------------
+// Program
+// --------------
+
+pub struct Program;
+
+impl Program {
+    /// Creates a shader program. The software renderer does not execute shaders, so the sources are ignored.
+    pub fn new(_context: &Context, _vertex_shader: &str, _fragment_shader: &str) -> core::Result<Program> {
+        Ok(Program)
+    }
+}
+
+// --------------
+// Monitor
+// --------------
+
+#[derive(Clone)]
+pub struct Monitor;
+
+impl Monitor {
+
+    /// Returns the device dimensions
+    pub fn get_dimensions(self: &Self) -> core::Point2<u32> {
+        (0, 0)
+    }
+
+    /// Returns the device name.
+    pub fn get_name(self: &Self) -> Option<String> {
+        Some("Headless".to_string())
+    }
+}
+
+pub struct MonitorIterator;
+
+impl MonitorIterator {
+
+    /// Returns an iterator over available monitors. There are none.
+    pub fn new() -> Self {
+        MonitorIterator
+    }
+}
+
+impl Iterator for MonitorIterator {
+    type Item = Monitor;
+    fn next(&mut self) -> Option<Monitor> {
+        None
+    }
+}
+
+// --------------
+// Texture2d
+// --------------
+
+pub struct Texture2d(RefCell<Surface>);
+
+impl Texture2d {
+    pub fn new(_context: &Context, width: u32, height: u32, format: core::TextureFormat, data: Option<core::RawFrame>) -> Self {
+        let clamp = clamps(format);
+        Texture2d(RefCell::new(if let Some(rawdata) = data {
+            Surface::from_raw(&rawdata, clamp)
+        } else {
+            Surface::new(width, height, clamp)
+        }))
+    }
+    pub fn clear(self: &Self, color: core::Color) {
+        self.0.borrow_mut().clear(color);
+    }
+    pub fn write(self: &Self, rect: &core::Rect<u32>, data: &Vec<u8>) {
+        let mut surface = self.0.borrow_mut();
+        let ((left, bottom), (right, top)) = *rect;
+        let width = right - left;
+        for y in 0..(top - bottom) {
+            for x in 0..width {
+                let value = data[(y * width + x) as usize] as f32 / 255.0;
+                surface.set(left + x, bottom + y, [ value, value, value, value ]);
+            }
+        }
+    }
+    pub fn copy_from(self: &Self, src_texture: &core::Texture, filter: core::TextureFilter) {
+        self.0.borrow_mut().copy_from(&src_texture.handle.0.borrow(), filter);
+    }
+    pub fn copy_rect_from(self: &Self, src_texture: &core::Texture, source_rect: core::Rect<i32>, target_rect: core::Rect<i32>, filter: core::TextureFilter) {
+        self.0.borrow_mut().copy_rect_from(&src_texture.handle.0.borrow(), source_rect, target_rect, filter);
+    }
+    pub fn copy_from_frame(self: &Self, src_frame: &Frame, filter: core::TextureFilter) {
+        self.0.borrow_mut().copy_from(&src_frame.surface.borrow(), filter);
+    }
+    pub fn copy_rect_from_frame(self: &Self, src_frame: &Frame, source_rect: core::Rect<i32>, target_rect: core::Rect<i32>, filter: core::TextureFilter) {
+        self.0.borrow_mut().copy_rect_from(&src_frame.surface.borrow(), source_rect, target_rect, filter);
+    }
+    pub fn read(self: &Self, rect: core::Rect<u32>) -> image::RgbaImage {
+        self.0.borrow().to_image(rect)
+    }
+}
+
+// --------------
+// Texture2dArray
+// --------------
+
+pub struct Texture2dArray(Vec<Surface>);
+
+impl Texture2dArray {
+    /// Generates a texture array from given vector of textures
+    pub fn new(_context: &Context, raw: &Vec<core::RawFrame>) -> Self {
+        Texture2dArray(raw.iter().map(|frame| Surface::from_raw(frame, true)).collect())
+    }
+}
+
+// --------------
+// Context
+// --------------
+
+pub struct Context;
+
+impl Context {
+
+    /// Creates a new backend context.
+    pub fn new(_display: &Display, _initial_capacity: usize) -> Self {
+        Context
+    }
+
+    /// Returns the maximum texture width and height. Software textures are only limited by available memory.
+    pub fn max_texture_size(self: &Self) -> u32 {
+        u32::max_value()
+    }
+}
+
+// --------------
+// Drawing
+// --------------
+
+/// A vertex transformed to window coordinates.
+#[derive(Copy, Clone)]
+struct RasterVertex {
+    position: [f32; 2],
+    uv      : [f32; 2],
+    color   : [f32; 4],
+}
+
+pub fn draw_layer(target: &core::RenderTarget, _program: &core::Program, context: &mut core::ContextData, layer: &core::Layer, component: u32, texture: Option<&core::Texture>) {
+
+    let view_matrix = *layer.view_matrix().deref().deref();
+    let model_matrix = *layer.model_matrix().deref().deref();
+    let layer_color: [f32; 4] = layer.color().deref().into();
+    let blendmode = *layer.blendmode();
+
+    layer.undirty();
+    let vertices = layer.vertices();
+    let vertices = vertices.deref();
+
+    let font_texture = context.font_texture.as_ref().unwrap().0.borrow();
+    let tex_arrays = &context.tex_arrays;
+    let atlas = context.atlas.as_ref().unwrap();
+    let layer_texture = texture.map(|texture| (texture.handle.0.borrow(), texture.magnify, texture.wrap));
+
+    with_target(target, |surface| {
+        for sprite in vertices.chunks(4).filter(|sprite| sprite.len() == 4) {
+
+            // sprite.vs: rotate offset, apply model matrix, translate to position and apply view matrix
+            let mut corners = [ RasterVertex { position: [ 0.0, 0.0 ], uv: [ 0.0, 0.0 ], color: [ 0.0; 4 ] }; 4 ];
+            for (corner, vertex) in corners.iter_mut().zip(sprite.iter()) {
+                let (sin, cos) = vertex.rotation.sin_cos();
+                let trans = [ vertex.offset[0] * cos - vertex.offset[1] * sin, vertex.offset[0] * sin + vertex.offset[1] * cos ];
+                let model = transform(&model_matrix, [ trans[0], trans[1], 0.0, 1.0 ]);
+                let position = transform(&view_matrix, [ vertex.position[0] + model[0], vertex.position[1] + model[1], model[2], 1.0 ]);
+                let color = [ vertex.color.0, vertex.color.1, vertex.color.2, vertex.color.3 ];
+                *corner = RasterVertex {
+                    position: to_window(surface, position),
+                    uv      : vertex.texture_uv,
+                    color   : multiply(color, layer_color),
+                };
+            }
+
+            // default.fs: sheet(v_tex_coords) * v_color
+            let vertex = &sprite[0];
+            let frames: &[Surface] = if vertex.bucket_id == core::ATLAS_BUCKET {
+                &atlas.data.0
+            } else if vertex.bucket_id == core::UNTEXTURED_BUCKET || vertex.bucket_id == core::LAYER_TEXTURE_BUCKET {
+                &[]
+            } else {
+                &tex_arrays[vertex.bucket_id as usize].data.0
+            };
+            let shade = |uv: [f32; 2], color: [f32; 4]| {
+                let texel = if vertex.bucket_id == 0 {
+                    font_texture.sample(uv, core::TextureFilter::Nearest, core::TextureWrap::Clamp)
+                } else if component >= vertex.components {
+                    [ 0.0; 4 ]
+                } else if vertex.bucket_id == core::UNTEXTURED_BUCKET {
+                    [ 1.0; 4 ]
+                } else if vertex.bucket_id == core::LAYER_TEXTURE_BUCKET {
+                    layer_texture.as_ref().map_or([ 0.0; 4 ], |&(ref source, filter, wrap)| source.sample(uv, filter, wrap))
+                } else if let Some(frame) = frames.get((vertex.texture_id + component) as usize) {
+                    frame.sample(uv, core::TextureFilter::Linear, core::TextureWrap::Clamp)
+                } else {
+                    [ 0.0; 4 ]
+                };
+                multiply(texel, color)
+            };
+
+            rasterize(surface, [ corners[0], corners[1], corners[2] ], &blendmode, &shade);
+            rasterize(surface, [ corners[1], corners[3], corners[2] ], &blendmode, &shade);
+        }
+    });
+}
+
+pub fn draw_rect<T>(target: &core::RenderTarget, _program: &core::Program, context: &mut core::ContextData, blend: core::BlendMode, info: core::DrawBuilder<T>, view_matrix: core::Mat4, model_matrix: core::Mat4, color: core::Color, texture: Option<&core::Texture>) {
+
+    let color: [f32; 4] = color.into();
+    let (offset, dimensions) = info.rect;
+
+    with_target(target, |surface| {
+
+        // texture.vs: scale unit rectangle to dimensions, apply model matrix, translate to offset and apply view matrix
+        let mut corners = [ RasterVertex { position: [ 0.0, 0.0 ], uv: [ 0.0, 0.0 ], color: color }; 4 ];
+        for (corner, vertex) in corners.iter_mut().zip(context.single_rect.iter()) {
+            let model = transform(&model_matrix, [ vertex.position[0] * dimensions.0, vertex.position[1] * dimensions.1, 0.0, 1.0 ]);
+            let position = transform(&view_matrix, [ offset.0 + model[0], offset.1 + model[1], model[2], 1.0 ]);
+            corner.position = to_window(surface, position);
+            corner.uv = vertex.texture_uv;
+        }
+
+        let source = texture.map(|texture| (texture.handle.0.borrow(), texture.magnify, texture.wrap));
+        let shade = |uv: [f32; 2], color: [f32; 4]| {
+            if let Some((ref source, filter, wrap)) = source {
+                multiply(source.sample(uv, filter, wrap), color)
+            } else {
+                color
+            }
+        };
+
+        rasterize(surface, [ corners[0], corners[1], corners[2] ], &blend, &shade);
+        rasterize(surface, [ corners[1], corners[3], corners[2] ], &blend, &shade);
+    });
+}
+
+/// Passes the surface of given render target to the given function.
+fn with_target<F>(target: &core::RenderTarget, func: F) where F: FnOnce(&mut Surface) {
+    match target.0 {
+        core::RenderTargetInner::Frame(ref display) => {
+            let mut frame = display.borrow_mut();
+            let frame = frame.as_mut().expect(core::NO_FRAME_PREPARED);
+            func(&mut frame.surface.borrow_mut());
+        }
+        core::RenderTargetInner::Texture(ref texture) => {
+            func(&mut texture.handle.0.borrow_mut());
+        }
+        core::RenderTargetInner::None => { }
+    }
+}
+
+/// Rasterizes a triangle, shading each covered pixel with given function and blending the result into the surface.
+fn rasterize<F>(surface: &mut Surface, vertices: [RasterVertex; 3], blendmode: &core::BlendMode, shade: &F) where F: Fn([f32; 2], [f32; 4]) -> [f32; 4] {
+
+    let (a, b, c) = (vertices[0], vertices[1], vertices[2]);
+    let area = edge(a.position, b.position, c.position);
+
+    if area == 0.0 || !area.is_finite() {
+        return;
+    }
+
+    // ensure counter-clockwise winding so that inside is positive for all edges
+    let (b, c, area) = if area < 0.0 { (c, b, -area) } else { (b, c, area) };
+
+    let min_x = a.position[0].min(b.position[0]).min(c.position[0]).floor().max(0.0) as u32;
+    let min_y = a.position[1].min(b.position[1]).min(c.position[1]).floor().max(0.0) as u32;
+    let max_x = a.position[0].max(b.position[0]).max(c.position[0]).ceil().min(surface.width as f32) as u32;
+    let max_y = a.position[1].max(b.position[1]).max(c.position[1]).ceil().min(surface.height as f32) as u32;
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let p = [ x as f32 + 0.5, y as f32 + 0.5 ];
+            let wa = edge(b.position, c.position, p);
+            let wb = edge(c.position, a.position, p);
+            let wc = edge(a.position, b.position, p);
+            if covers(wa, b.position, c.position) && covers(wb, c.position, a.position) && covers(wc, a.position, b.position) {
+                let (wa, wb, wc) = (wa / area, wb / area, wc / area);
+                let uv = [
+                    a.uv[0] * wa + b.uv[0] * wb + c.uv[0] * wc,
+                    a.uv[1] * wa + b.uv[1] * wb + c.uv[1] * wc,
+                ];
+                let mut color = [ 0.0; 4 ];
+                for i in 0..4 {
+                    color[i] = a.color[i] * wa + b.color[i] * wb + c.color[i] * wc;
+                }
+                surface.blend(x, y, shade(uv, color), blendmode);
+            }
+        }
+    }
+}
+
+/// Edge function. Positive if p is left of the edge from a to b.
+fn edge(a: [f32; 2], b: [f32; 2], p: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+/// Returns whether a pixel with given edge value is covered. Pixels exactly on an edge are only
+/// covered by top or left edges so that pixels on edges shared by two triangles are drawn once.
+fn covers(value: f32, a: [f32; 2], b: [f32; 2]) -> bool {
+    value > 0.0 || (value == 0.0 && ((a[1] == b[1] && b[0] < a[0]) || b[1] < a[1]))
+}
+
+/// Multiplies a column-major matrix with a vector.
+fn transform(matrix: &core::Mat4, vector: [f32; 4]) -> [f32; 4] {
+    let mut result = [ 0.0; 4 ];
+    for row in 0..4 {
+        for column in 0..4 {
+            result[row] += matrix[column][row] * vector[column];
+        }
+    }
+    result
+}
+
+/// Converts clip space coordinates to window coordinates of given surface.
+fn to_window(surface: &Surface, position: [f32; 4]) -> [f32; 2] {
+    let x = position[0] / position[3];
+    let y = position[1] / position[3];
+    [ (x + 1.0) * 0.5 * surface.width as f32, (y + 1.0) * 0.5 * surface.height as f32 ]
+}
+
+/// Blends source color into destination color.
+fn blend(source: [f32; 4], destination: [f32; 4], blendmode: &core::BlendMode) -> [f32; 4] {
+
+    fn factor(factor: core::LinearBlendingFactor, channel: usize, source: &[f32; 4], destination: &[f32; 4], constant: &[f32; 4]) -> f32 {
+        use core::LinearBlendingFactor as LBF;
+        match factor {
+            LBF::Zero                      => 0.0,
+            LBF::One                       => 1.0,
+            LBF::SourceColor               => source[channel],
+            LBF::OneMinusSourceColor       => 1.0 - source[channel],
+            LBF::DestinationColor          => destination[channel],
+            LBF::OneMinusDestinationColor  => 1.0 - destination[channel],
+            LBF::SourceAlpha               => source[3],
+            LBF::OneMinusSourceAlpha       => 1.0 - source[3],
+            LBF::DestinationAlpha          => destination[3],
+            LBF::OneMinusDestinationAlpha  => 1.0 - destination[3],
+            LBF::SourceAlphaSaturate       => if channel == 3 { 1.0 } else { source[3].min(1.0 - destination[3]) },
+            LBF::ConstantColor             => constant[channel],
+            LBF::OneMinusConstantColor     => 1.0 - constant[channel],
+            LBF::ConstantAlpha             => constant[3],
+            LBF::OneMinusConstantAlpha     => 1.0 - constant[3],
+        }
+    }
+
+    fn function(function: core::BlendingFunction, channel: usize, source: &[f32; 4], destination: &[f32; 4], constant: &[f32; 4]) -> f32 {
+        use core::BlendingFunction as BF;
+        let s = source[channel];
+        let d = destination[channel];
+        match function {
+            BF::AlwaysReplace                               => s,
+            BF::Min                                         => s.min(d),
+            BF::Max                                         => s.max(d),
+            BF::Addition { source: sf, destination: df }           => s * factor(sf, channel, source, destination, constant) + d * factor(df, channel, source, destination, constant),
+            BF::Subtraction { source: sf, destination: df }        => s * factor(sf, channel, source, destination, constant) - d * factor(df, channel, source, destination, constant),
+            BF::ReverseSubtraction { source: sf, destination: df } => d * factor(df, channel, source, destination, constant) - s * factor(sf, channel, source, destination, constant),
+        }
+    }
+
+    let constant: [f32; 4] = blendmode.constant_value.into();
+
+    [
+        function(blendmode.color, 0, &source, &destination, &constant),
+        function(blendmode.color, 1, &source, &destination, &constant),
+        function(blendmode.color, 2, &source, &destination, &constant),
+        function(blendmode.alpha, 3, &source, &destination, &constant),
+    ]
+}
+
+// --------------
+// Misc
+// --------------
+
+// Returns whether given format stores normalized values.
+fn clamps(format: core::TextureFormat) -> bool {
+    use core::TextureFormat as TF;
+    match format {
+        TF::F16 | TF::F16F16 | TF::F16F16F16F16 | TF::F32 | TF::F32F32 | TF::F32F32F32F32 | TF::F11F11F10 => false,
+        _ => true,
+    }
+}
+
+// Maps a texel coordinate into the range 0..size using given wrapping function.
+fn wrap_coord(coord: i32, size: u32, wrap: core::TextureWrap) -> u32 {
+    use core::TextureWrap as TW;
+    let size = size as i32;
+    let coord = match wrap {
+        TW::Clamp => coord.max(0).min(size - 1),
+        TW::Repeat => ((coord % size) + size) % size,
+        TW::Mirror => {
+            let period = ((coord % (size * 2)) + size * 2) % (size * 2);
+            if period < size { period } else { size * 2 - 1 - period }
+        }
+        TW::MirrorClamp => {
+            let coord = if coord < 0 { -coord - 1 } else { coord };
+            coord.min(size - 1)
+        }
+    };
+    coord as u32
+}
+
+// Linearly interpolates between two colors.
+fn mix(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    [ a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, a[2] + (b[2] - a[2]) * t, a[3] + (b[3] - a[3]) * t ]
+}
+
+// Multiplies two colors component-wise.
+fn multiply(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    [ a[0] * b[0], a[1] * b[1], a[2] * b[2], a[3] * b[3] ]
+}
+
+// Clamps a value to 0.0 - 1.0.
+fn clamp(value: f32) -> f32 {
+    value.max(0.0).min(1.0)
+}
+
+// Converts a normalized value to u8.
+fn to_u8(value: f32) -> u8 {
+    (clamp(value) * 255.0).round() as u8
+}
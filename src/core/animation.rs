@@ -0,0 +1,209 @@
+use prelude::*;
+use core::{Sprite, Layer, Color, Point2, AnimationBuilder};
+
+/// Playback mode of an animation clip.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AnimationMode {
+    /// Plays the clip once and stops on its last frame.
+    Once,
+    /// Restarts the clip from its first frame after the last frame.
+    Loop,
+    /// Plays the clip forward, then backward, then repeats.
+    PingPong,
+}
+
+/// Events reported by [`AnimationState::update()`](struct.AnimationState.html#method.update).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AnimationEvent {
+    /// A looping clip completed the given number of cycles. For ping-pong clips, a cycle is one pass forward and back.
+    Looped(u32),
+    /// A clip played with `AnimationMode::Once` reached its end.
+    Finished,
+}
+
+/// A named sequence of sprite frames.
+#[derive(Clone, Debug)]
+pub(crate) struct AnimationClip {
+    pub name    : String,
+    pub mode    : AnimationMode,
+    /// Frame ids and durations of one cycle of the clip. Ping-pong clips contain the backward pass.
+    pub steps   : Vec<(u32, f32)>,
+    pub duration: f32,
+}
+
+/// A set of named animation clips for a sprite.
+///
+/// An animation only describes clips. Playback position and speed are kept separately in an
+/// [`AnimationState`](struct.AnimationState.html), so a single animation can be shared (e.g. in an `Arc`) by any
+/// number of animated objects and threads.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use radiant_rs::*;
+/// # let display = Display::builder().hidden().build().unwrap();
+/// # let layer = Layer::new((320., 200.));
+/// # let sprite = Sprite::from_file(display.context(), "examples/res/sprites/ball_v2_32x32x18.jpg").unwrap();
+/// let animation = Animation::builder(&sprite)
+///     .frame_duration(0.05)
+///     .clip("spin", 0..18, AnimationMode::Loop)
+///     .clip("bounce", vec![ 0, 4, 8 ], AnimationMode::PingPong)
+///     .build();
+///
+/// let mut state = animation.state("spin");
+///
+/// // once per frame
+/// state.update(&animation, 1.0 / 60.0);
+/// animation.draw_animation(&layer, &state, (160., 100.), Color::WHITE);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Animation {
+    sprite  : Sprite,
+    clips   : Arc<Vec<AnimationClip>>,
+}
+
+impl Animation {
+
+    /// Returns an [animation builder](support/struct.AnimationBuilder.html) for defining clips of given sprite.
+    pub fn builder(sprite: &Sprite) -> AnimationBuilder {
+        AnimationBuilder::new(sprite)
+    }
+
+    /// Returns the animated sprite.
+    pub fn sprite(self: &Self) -> &Sprite {
+        &self.sprite
+    }
+
+    /// Returns whether the animation has a clip of given name.
+    pub fn has_clip(self: &Self, clip: &str) -> bool {
+        self.clip_index(clip).is_some()
+    }
+
+    /// Returns the duration of one cycle of given clip in seconds.
+    pub fn clip_duration(self: &Self, clip: &str) -> Option<f32> {
+        self.clip_index(clip).map(|index| self.clips[index].duration)
+    }
+
+    /// Returns a new playback state for given clip, starting at its first frame. Panics if there is no clip of given name.
+    pub fn state(self: &Self, clip: &str) -> AnimationState {
+        AnimationState {
+            clip    : self.expect_clip(clip),
+            time    : 0.0,
+            speed   : 1.0,
+            finished: false,
+        }
+    }
+
+    /// Draws the current frame of given animation state onto the given layer.
+    pub fn draw_animation<T>(self: &Self, layer: &Layer, state: &AnimationState, position: T, color: Color) -> &Self where Point2: From<T> {
+        self.sprite.draw(layer, state.frame_id(self), position, color);
+        self
+    }
+
+    /// Draws the current frame of given animation state onto the given layer and applies given color, rotation and scaling.
+    pub fn draw_animation_transformed<T, U>(self: &Self, layer: &Layer, state: &AnimationState, position: T, color: Color, rotation: f32, scale: U) -> &Self where Point2: From<T>, Point2: From<U> {
+        self.sprite.draw_transformed(layer, state.frame_id(self), position, color, rotation, scale);
+        self
+    }
+
+    /// Creates an animation from given sprite and clips.
+    pub(crate) fn new(sprite: &Sprite, clips: Vec<AnimationClip>) -> Self {
+        Animation {
+            sprite  : sprite.clone(),
+            clips   : Arc::new(clips),
+        }
+    }
+
+    /// Returns the index of the clip with given name.
+    fn clip_index(self: &Self, clip: &str) -> Option<usize> {
+        self.clips.iter().position(|candidate| candidate.name == clip)
+    }
+
+    /// Returns the index of the clip with given name or panics.
+    fn expect_clip(self: &Self, clip: &str) -> usize {
+        self.clip_index(clip).unwrap_or_else(|| panic!("Animation has no clip named {:?}.", clip))
+    }
+}
+
+/// Playback state of an [`Animation`](struct.Animation.html).
+///
+/// States are plain values, cheap to copy and independent of the animation they were created from. Each animated
+/// object keeps its own state and advances it with [`update()`](#method.update).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AnimationState {
+    clip    : usize,
+    time    : f32,
+    speed   : f32,
+    finished: bool,
+}
+
+impl AnimationState {
+
+    /// Switches to given clip of the animation and restarts playback. The playback speed is retained. Panics if
+    /// there is no clip of given name.
+    pub fn play(self: &mut Self, animation: &Animation, clip: &str) {
+        self.clip = animation.expect_clip(clip);
+        self.time = 0.0;
+        self.finished = false;
+    }
+
+    /// Advances playback by `delta` seconds, multiplied by the playback speed. Returns an event if the clip ended
+    /// or looped during the update.
+    pub fn update(self: &mut Self, animation: &Animation, delta: f32) -> Option<AnimationEvent> {
+        let clip = &animation.clips[self.clip];
+        if self.finished || clip.duration <= 0.0 {
+            return None;
+        }
+        self.time += delta * self.speed;
+        if self.time < clip.duration {
+            None
+        } else if clip.mode == AnimationMode::Once {
+            self.time = clip.duration;
+            self.finished = true;
+            Some(AnimationEvent::Finished)
+        } else {
+            let cycles = (self.time / clip.duration).floor();
+            self.time -= cycles * clip.duration;
+            Some(AnimationEvent::Looped(cycles as u32))
+        }
+    }
+
+    /// Returns the sprite frame id to draw for the current playback position.
+    pub fn frame_id(self: &Self, animation: &Animation) -> u32 {
+        let clip = &animation.clips[self.clip];
+        let mut end = 0.0;
+        for &(frame_id, duration) in clip.steps.iter() {
+            end += duration;
+            if self.time < end {
+                return frame_id;
+            }
+        }
+        clip.steps.last().map_or(0, |&(frame_id, _)| frame_id)
+    }
+
+    /// Returns the name of the playing clip.
+    pub fn clip<'a>(self: &Self, animation: &'a Animation) -> &'a str {
+        &animation.clips[self.clip].name
+    }
+
+    /// Returns the playback position within the current cycle of the clip in seconds.
+    pub fn time(self: &Self) -> f32 {
+        self.time
+    }
+
+    /// Sets the playback speed. 1.0 is normal speed, 0.0 pauses playback.
+    pub fn set_speed(self: &mut Self, speed: f32) {
+        assert!(speed >= 0.0, "Animation speed must not be negative.");
+        self.speed = speed;
+    }
+
+    /// Returns the playback speed.
+    pub fn speed(self: &Self) -> f32 {
+        self.speed
+    }
+
+    /// Returns whether a clip played with `AnimationMode::Once` has reached its end.
+    pub fn is_finished(self: &Self) -> bool {
+        self.finished
+    }
+}
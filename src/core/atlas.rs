@@ -0,0 +1,276 @@
+use prelude::*;
+use core::{self, SpriteData, RawFrame, Rect};
+use backends::backend;
+
+/// Bucket id used for sprites stored in the texture atlas.
+pub const ATLAS_BUCKET: u32 = 255;
+
+/// Width and height of a single atlas page.
+pub const ATLAS_PAGE_SIZE: u32 = 2048;
+
+/// Storage mode for sprite frames loaded into a [`Context`](struct.Context.html).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SpriteStorage {
+    /// Each frame is padded up to the next power of two and stored in a texture array of that size.
+    Buckets,
+    /// Frames are packed into shared atlas pages, surrounded by `padding` pixels that repeat the
+    /// frame's edge to avoid bleeding with linear filtering.
+    Atlas { padding: u32 },
+}
+
+impl Default for SpriteStorage {
+    fn default() -> SpriteStorage {
+        SpriteStorage::Buckets
+    }
+}
+
+/// Skyline rectangle packer.
+struct Skyline {
+    width   : u32,
+    height  : u32,
+    // x, y and width of each skyline segment, ordered by x
+    nodes   : Vec<(u32, u32, u32)>,
+}
+
+impl Skyline {
+    fn new(width: u32, height: u32) -> Self {
+        Skyline {
+            width   : width,
+            height  : height,
+            nodes   : vec![ (0, 0, width) ],
+        }
+    }
+
+    /// Finds the lowest position the rectangle fits at and reserves it. Returns top/left coordinates or None
+    /// if the rectangle does not fit.
+    fn insert(self: &mut Self, width: u32, height: u32) -> Option<(u32, u32)> {
+
+        let mut best: Option<(usize, u32, u32)> = None;
+
+        for index in 0..self.nodes.len() {
+            if let Some(y) = self.fit(index, width, height) {
+                let x = self.nodes[index].0;
+                if best.map_or(true, |(_, best_x, best_y)| y < best_y || (y == best_y && x < best_x)) {
+                    best = Some((index, x, y));
+                }
+            }
+        }
+
+        best.map(|(index, x, y)| {
+            self.reserve(index, x, y + height, width);
+            (x, y)
+        })
+    }
+
+    /// Returns the y coordinate a rectangle starting at given node would be placed at.
+    fn fit(self: &Self, index: usize, width: u32, height: u32) -> Option<u32> {
+        let x = self.nodes[index].0;
+        if x + width > self.width {
+            return None;
+        }
+        let mut y = 0;
+        let mut remaining = width as i64;
+        let mut current = index;
+        while remaining > 0 {
+            let (_, node_y, node_width) = self.nodes[current];
+            y = cmp::max(y, node_y);
+            if y + height > self.height {
+                return None;
+            }
+            remaining -= node_width as i64;
+            current += 1;
+        }
+        Some(y)
+    }
+
+    /// Inserts a new skyline segment and shrinks or removes the segments it covers.
+    fn reserve(self: &mut Self, index: usize, x: u32, y: u32, width: u32) {
+        self.nodes.insert(index, (x, y, width));
+        let right = x + width;
+        while index + 1 < self.nodes.len() {
+            let (next_x, next_y, next_width) = self.nodes[index + 1];
+            if next_x >= right {
+                break;
+            }
+            let next_right = next_x + next_width;
+            if next_right <= right {
+                self.nodes.remove(index + 1);
+            } else {
+                self.nodes[index + 1] = (right, next_y, next_right - right);
+                break;
+            }
+        }
+        // merge neighbouring segments of equal height
+        let mut i = 0;
+        while i + 1 < self.nodes.len() {
+            if self.nodes[i].1 == self.nodes[i + 1].1 {
+                self.nodes[i].2 += self.nodes[i + 1].2;
+                self.nodes.remove(i + 1);
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+/// A page of the atlas. Each page spans one texture array layer per sprite component.
+struct AtlasPage {
+    components  : u32,
+    first_layer : usize,
+    packer      : Skyline,
+}
+
+/// Sprite frames packed into shared texture array layers.
+pub struct Atlas {
+    pub dirty   : bool,
+    pub data    : backend::Texture2dArray,
+    raw         : Vec<RawFrame>,
+    pages       : Vec<AtlasPage>,
+    sprites     : Vec<Weak<SpriteData>>,
+}
+
+impl Atlas {
+    pub fn new(context: &backend::Context) -> Self {
+        Atlas {
+            dirty   : false,
+            data    : backend::Texture2dArray::new(context, &Vec::new()),
+            raw     : Vec::new(),
+            pages   : Vec::new(),
+            sprites : Vec::new(),
+        }
+    }
+
+    /// Packs given frames into the atlas. `raw_frames` contains `components` consecutive raw frames per sprite
+    /// frame. Returns the texture array layer and uv rectangle for each sprite frame.
+    pub fn store_frames(self: &mut Self, raw_frames: Vec<RawFrame>, components: u32, padding: u32) -> core::Result<Vec<(u32, Rect)>> {
+
+        let mut result = Vec::new();
+
+        for frame in raw_frames.chunks(components as usize) {
+
+            let (width, height) = (frame[0].width, frame[0].height);
+            let (padded_width, padded_height) = (width + padding * 2, height + padding * 2);
+
+            if padded_width > ATLAS_PAGE_SIZE || padded_height > ATLAS_PAGE_SIZE {
+                return Err(core::Error::SpriteSizeError(format!("Sprite frame of {}x{} pixels exceeds atlas page size of {}x{} pixels", width, height, ATLAS_PAGE_SIZE, ATLAS_PAGE_SIZE)));
+            }
+
+            let (page_id, (x, y)) = self.allocate(components, padded_width, padded_height);
+            let first_layer = self.pages[page_id].first_layer;
+
+            for (component, raw_frame) in frame.iter().enumerate() {
+                Self::write_extruded(&mut self.raw[first_layer + component], raw_frame, x, y, padding);
+            }
+
+            let size = ATLAS_PAGE_SIZE as f32;
+            let (left, top) = ((x + padding) as f32 / size, (y + padding) as f32 / size);
+            result.push((first_layer as u32, ((left, top), (left + width as f32 / size, top + height as f32 / size))));
+        }
+
+        self.dirty = true;
+        Ok(result)
+    }
+
+    /// Stores a weak sprite reference so that the sprite's texture ids can be updated after a cleanup.
+    pub fn store_sprite(self: &mut Self, sprite_data: Weak<SpriteData>) {
+        self.sprites.push(sprite_data);
+    }
+
+    /// Updates texture array in video memory.
+    pub fn update(self: &mut Self, context: &backend::Context) {
+        if self.dirty {
+            self.dirty = false;
+            self.data = backend::Texture2dArray::new(context, &self.raw);
+        }
+    }
+
+    /// Removes pages no longer referenced by any sprite and updates sprite texture ids and generations.
+    /// Space freed by dropped sprites on pages that are still in use is not reclaimed.
+    pub fn prune(self: &mut Self, context: &backend::Context, generation: usize) {
+
+        self.sprites.retain(|sprite| sprite.upgrade().is_some());
+        let sprites: Vec<Arc<SpriteData>> = self.sprites.iter().filter_map(|sprite| sprite.upgrade()).collect();
+
+        // Find pages still referenced by a sprite.
+        let mut used = vec![ false; self.pages.len() ];
+        for sprite in sprites.iter() {
+            for frame in sprite.frames.iter() {
+                let layer = frame.texture_id.load(Ordering::Relaxed);
+                if let Some(page_id) = self.pages.iter().position(|page| page.first_layer == layer) {
+                    used[page_id] = true;
+                }
+            }
+        }
+
+        // Compact layers of used pages.
+        if used.iter().any(|&used| !used) {
+            let mut old_raw: Vec<Option<RawFrame>> = mem::replace(&mut self.raw, Vec::new()).into_iter().map(|frame| Some(frame)).collect();
+            let mut layer_map = HashMap::new();
+            let old_pages = mem::replace(&mut self.pages, Vec::new());
+            for (page, _) in old_pages.into_iter().zip(used.iter()).filter(|&(_, &used)| used) {
+                let first_layer = self.raw.len();
+                for layer in page.first_layer..page.first_layer + page.components as usize {
+                    self.raw.push(old_raw[layer].take().unwrap());
+                }
+                layer_map.insert(page.first_layer, first_layer);
+                self.pages.push(AtlasPage { first_layer: first_layer, ..page });
+            }
+            for sprite in sprites.iter() {
+                for frame in sprite.frames.iter() {
+                    let layer = frame.texture_id.load(Ordering::Relaxed);
+                    frame.texture_id.store(layer_map[&layer], Ordering::Relaxed);
+                }
+            }
+            self.dirty = true;
+            self.update(context);
+        }
+
+        for sprite in sprites.iter() {
+            sprite.generation.store(generation, Ordering::Relaxed);
+        }
+    }
+
+    /// Reserves space for a padded frame, adding a new page if necessary. Returns page id and top/left position.
+    fn allocate(self: &mut Self, components: u32, width: u32, height: u32) -> (usize, (u32, u32)) {
+        for (page_id, page) in self.pages.iter_mut().enumerate().filter(|&(_, ref page)| page.components == components) {
+            if let Some(position) = page.packer.insert(width, height) {
+                return (page_id, position);
+            }
+        }
+        let mut page = AtlasPage {
+            components  : components,
+            first_layer : self.raw.len(),
+            packer      : Skyline::new(ATLAS_PAGE_SIZE, ATLAS_PAGE_SIZE),
+        };
+        let position = page.packer.insert(width, height).unwrap();
+        for _ in 0..components {
+            self.raw.push(RawFrame {
+                data    : vec![ 0u8; ATLAS_PAGE_SIZE as usize * ATLAS_PAGE_SIZE as usize * 4 ],
+                width   : ATLAS_PAGE_SIZE,
+                height  : ATLAS_PAGE_SIZE,
+                channels: 4,
+            });
+        }
+        self.pages.push(page);
+        (self.pages.len() - 1, position)
+    }
+
+    /// Copies a frame into a page layer at given position, repeating its edge pixels `padding` times.
+    fn write_extruded(layer: &mut RawFrame, frame: &RawFrame, x: u32, y: u32, padding: u32) {
+        if frame.width == 0 || frame.height == 0 {
+            return;
+        }
+        let channels = frame.channels as usize;
+        for target_y in 0..frame.height + padding * 2 {
+            let source_y = cmp::min(cmp::max(target_y as i64 - padding as i64, 0) as u32, frame.height - 1);
+            for target_x in 0..frame.width + padding * 2 {
+                let source_x = cmp::min(cmp::max(target_x as i64 - padding as i64, 0) as u32, frame.width - 1);
+                let source = (source_y * frame.width + source_x) as usize * channels;
+                let target = ((y + target_y) * layer.width + x + target_x) as usize * 4;
+                for channel in 0..4 {
+                    layer.data[target + channel] = frame.data[source + cmp::min(channel, channels - 1)];
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use core::{Sprite, Animation, AnimationMode};
+use core::animation::AnimationClip;
+
+/// An animation builder.
+///
+/// Obtained from [`Animation::builder()`](../struct.Animation.html#method.builder).
+///
+/// Frames without an explicit duration use the duration given by the sprite's sheet descriptor or, if
+/// there is none, the builder's [`frame_duration()`](#method.frame_duration).
+#[must_use]
+#[derive(Clone)]
+pub struct AnimationBuilder<'a> {
+    sprite          : &'a Sprite,
+    frame_duration  : f32,
+    clips           : Vec<(String, AnimationMode, Vec<(u32, Option<f32>)>)>,
+}
+
+impl<'a> AnimationBuilder<'a> {
+    /// Sets the default frame duration in seconds. Defaults to 0.1.
+    pub fn frame_duration(mut self: Self, seconds: f32) -> Self {
+        self.frame_duration = seconds;
+        self
+    }
+    /// Adds a clip consisting of given frame ids, e.g. a range `0..8` or a list `vec![ 3, 4, 3, 5 ]`.
+    pub fn clip<T>(self: Self, name: &str, frames: T, mode: AnimationMode) -> Self where T: IntoIterator<Item=u32> {
+        self.add_clip(name, mode, frames.into_iter().map(|frame_id| (frame_id, None)).collect())
+    }
+    /// Adds a clip consisting of given frame ids and durations in seconds.
+    pub fn timed_clip<T>(self: Self, name: &str, frames: T, mode: AnimationMode) -> Self where T: IntoIterator<Item=(u32, f32)> {
+        self.add_clip(name, mode, frames.into_iter().map(|(frame_id, duration)| (frame_id, Some(duration))).collect())
+    }
+    /// Returns the constructed animation instance.
+    pub fn build(self: Self) -> Animation {
+        let sprite = self.sprite;
+        let frame_duration = self.frame_duration;
+        let clips = self.clips.into_iter().map(|(name, mode, frames)| {
+            let mut steps: Vec<(u32, f32)> = frames.into_iter().map(|(frame_id, duration)| {
+                (frame_id, duration.or_else(|| sprite.frame_duration(frame_id)).unwrap_or(frame_duration))
+            }).collect();
+            if mode == AnimationMode::PingPong && steps.len() > 2 {
+                let backward: Vec<_> = steps[1..steps.len() - 1].iter().rev().cloned().collect();
+                steps.extend(backward);
+            }
+            AnimationClip {
+                name    : name,
+                mode    : mode,
+                duration: steps.iter().map(|&(_, duration)| duration).sum(),
+                steps   : steps,
+            }
+        }).collect();
+        Animation::new(sprite, clips)
+    }
+    // Adds a clip with optional frame durations.
+    fn add_clip(mut self: Self, name: &str, mode: AnimationMode, frames: Vec<(u32, Option<f32>)>) -> Self {
+        assert!(frames.len() > 0, "Animation clip must contain at least one frame.");
+        self.clips.push((name.to_string(), mode, frames));
+        self
+    }
+    // Creates a new AnimationBuilder instance.
+    pub(crate) fn new<'b>(sprite: &'b Sprite) -> AnimationBuilder {
+        AnimationBuilder {
+            sprite          : sprite,
+            frame_duration  : 0.1,
+            clips           : Vec::new(),
+        }
+    }
+}
@@ -3,9 +3,18 @@ mod fontbuilder;
 mod fontquerybuilder;
 mod texturebuilder;
 mod drawbuilder;
+mod animationbuilder;
+mod spritedrawbuilder;
+mod sliceddrawbuilder;
+mod textbuilder;
 
 pub use self::displaybuilder::DisplayBuilder;
 pub use self::fontbuilder::FontBuilder;
 pub use self::fontquerybuilder::FontQueryBuilder;
 pub use self::texturebuilder::TextureBuilder;
+pub use self::animationbuilder::AnimationBuilder;
+pub use self::spritedrawbuilder::SpriteDrawBuilder;
+pub use self::sliceddrawbuilder::SlicedDrawBuilder;
+pub use self::textbuilder::TextBuilder;
+pub(crate) use self::textbuilder::TextSource;
 pub use self::drawbuilder::{DrawBuilder, DrawBuilderFill, DrawBuilderRect, DrawBuilderViewSource};
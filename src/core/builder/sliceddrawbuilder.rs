@@ -0,0 +1,80 @@
+use core::{Sprite, Layer, Color};
+use core::math::*;
+
+/// A nine-slice sprite drawing builder.
+///
+/// Obtained from [`Sprite::draw_sliced()`](../struct.Sprite.html#method.draw_sliced).
+///
+/// The frame is divided into nine slices by the border insets. Corners are drawn unscaled, edges are stretched along
+/// the border and the center is stretched in both directions to fill the target rectangle. If the rectangle is
+/// smaller than the combined insets, the borders are scaled down to fit.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use radiant_rs::*;
+/// # let display = Display::builder().hidden().build().unwrap();
+/// # let layer = Layer::new((320., 200.));
+/// # let sprite = Sprite::from_file(display.context(), "examples/res/sprites/ball_v2_32x32x18.jpg").unwrap();
+/// // a 200x80 panel with 8 pixel borders, repeating the edges and the center instead of stretching them
+/// sprite.draw_sliced(&layer, 0, ((60., 60.), (200., 80.)))
+///     .insets((8., 8., 8., 8.))
+///     .tile()
+///     .draw();
+/// ```
+#[must_use]
+#[derive(Clone)]
+pub struct SlicedDrawBuilder<'a> {
+    sprite                  : &'a Sprite,
+    layer                   : &'a Layer,
+    pub(crate) frame_id     : u32,
+    pub(crate) rect         : Rect,
+    pub(crate) insets       : Option<(f32, f32, f32, f32)>,
+    pub(crate) color        : Color,
+    pub(crate) tile         : bool,
+    pub(crate) z            : f32,
+}
+
+impl<'a> SlicedDrawBuilder<'a> {
+    /// Sets the border insets (left, top, right, bottom) in pixels of the frame. Defaults to the insets given by
+    /// the sprite's sheet descriptor or, if there are none, to zero.
+    pub fn insets(mut self: Self, insets: (f32, f32, f32, f32)) -> Self {
+        self.insets = Some(insets);
+        self
+    }
+    /// Sets a color for drawing. Defaults to white.
+    pub fn color(mut self: Self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+    /// Repeats the edges and the center at their original size instead of stretching them. Tiles at the far
+    /// end are cropped to fit. Parts narrower than a pixel are repeated at one pixel, parts that would require
+    /// more than 256 tiles along an axis are stretched.
+    pub fn tile(mut self: Self) -> Self {
+        self.tile = true;
+        self
+    }
+    /// Sets the sort key used by layers with a [`LayerSortMode`](../enum.LayerSortMode.html) other than
+    /// `Insertion`. Defaults to 0.0.
+    pub fn z(mut self: Self, z: f32) -> Self {
+        self.z = z;
+        self
+    }
+    /// Draws the sprite.
+    pub fn draw(self: Self) {
+        self.sprite.draw_from_sliced_builder(self.layer, &self);
+    }
+    // Creates a new SlicedDrawBuilder instance.
+    pub(crate) fn new(sprite: &'a Sprite, layer: &'a Layer, frame_id: u32, rect: Rect) -> SlicedDrawBuilder<'a> {
+        SlicedDrawBuilder {
+            sprite      : sprite,
+            layer       : layer,
+            frame_id    : frame_id,
+            rect        : rect,
+            insets      : None,
+            color       : Color::WHITE,
+            tile        : false,
+            z           : 0.0,
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use core::{Sprite, Layer, Color};
+use core::math::*;
+
+/// A sprite drawing builder.
+///
+/// Obtained from [`Sprite::draw_builder()`](../struct.Sprite.html#method.draw_builder).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use radiant_rs::*;
+/// # let display = Display::builder().hidden().build().unwrap();
+/// # let layer = Layer::new((320., 200.));
+/// # let sprite = Sprite::from_file(display.context(), "examples/res/sprites/ball_v2_32x32x18.jpg").unwrap();
+/// // draw the left 70% of frame 3, mirrored horizontally
+/// sprite.draw_builder(&layer, 3)
+///     .position((160., 100.))
+///     .crop(((0., 0.), (0.7, 1.)))
+///     .flip_x()
+///     .draw();
+///
+/// // fade frame 0 from opaque at the top to transparent at the bottom, rotated and at double size
+/// sprite.draw_builder(&layer, 0)
+///     .position((80., 50.))
+///     .rotation(0.5)
+///     .scale((2., 2.))
+///     .gradient_y(Color::WHITE, Color::TRANSPARENT)
+///     .draw();
+/// ```
+#[must_use]
+#[derive(Clone)]
+pub struct SpriteDrawBuilder<'a> {
+    sprite                  : &'a Sprite,
+    layer                   : &'a Layer,
+    pub(crate) frame_id     : u32,
+    pub(crate) position     : Point2,
+    pub(crate) colors       : [ Color; 4 ],
+    pub(crate) component    : Option<u32>,
+    pub(crate) rotation     : f32,
+    pub(crate) scale        : Point2,
+    pub(crate) anchor       : Option<Point2>,
+    pub(crate) flip         : (bool, bool),
+    pub(crate) crop         : Rect,
+    pub(crate) skew         : Point2,
+    pub(crate) z            : f32,
+}
+
+impl<'a> SpriteDrawBuilder<'a> {
+    /// Sets the position on the layer. Defaults to (0.0, 0.0).
+    pub fn position<T>(mut self: Self, position: T) -> Self where Point2: From<T> {
+        self.position = Point2::from(position);
+        self
+    }
+    /// Sets a color for drawing. Defaults to white.
+    pub fn color(mut self: Self, color: Color) -> Self {
+        self.colors = [ color; 4 ];
+        self
+    }
+    /// Sets individual colors for the top left, top right, bottom left and bottom right corner of the drawn
+    /// sprite. Colors are interpolated across the sprite, e.g. for gradients or per-vertex lighting. Corners refer
+    /// to the sprite as it appears on the layer after flipping but before rotation.
+    pub fn colors(mut self: Self, top_left: Color, top_right: Color, bottom_left: Color, bottom_right: Color) -> Self {
+        self.colors = [ top_left, top_right, bottom_left, bottom_right ];
+        self
+    }
+    /// Sets a color for the left and the right edge of the drawn sprite.
+    pub fn gradient_x(self: Self, left: Color, right: Color) -> Self {
+        self.colors(left, right, left, right)
+    }
+    /// Sets a color for the top and the bottom edge of the drawn sprite.
+    pub fn gradient_y(self: Self, top: Color, bottom: Color) -> Self {
+        self.colors(top, top, bottom, bottom)
+    }
+    /// Draws only the given component of a multi-component sprite, as if it was the sprite's only (color) component.
+    /// Panics if the sprite has fewer components.
+    pub fn component(mut self: Self, component: u32) -> Self {
+        assert!(component < self.sprite.num_components(), "Sprite has no component {}.", component);
+        self.component = Some(component);
+        self
+    }
+    /// Sets the rotation in radians around the anchor.
+    pub fn rotation(mut self: Self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+    /// Sets a horizontal and vertical scaling factor. Defaults to (1.0, 1.0).
+    pub fn scale<T>(mut self: Self, scale: T) -> Self where Point2: From<T> {
+        self.scale = Point2::from(scale);
+        self
+    }
+    /// Overrides the anchor set with [`Sprite::set_anchor()`](../struct.Sprite.html#method.set_anchor) or the frame's pivot for this draw.
+    pub fn anchor<T>(mut self: Self, anchor: T) -> Self where Point2: From<T> {
+        self.anchor = Some(Point2::from(anchor));
+        self
+    }
+    /// Mirrors the frame horizontally around the anchor.
+    pub fn flip_x(mut self: Self) -> Self {
+        self.flip.0 = !self.flip.0;
+        self
+    }
+    /// Mirrors the frame vertically around the anchor.
+    pub fn flip_y(mut self: Self) -> Self {
+        self.flip.1 = !self.flip.1;
+        self
+    }
+    /// Draws only a portion of the frame, given as `((left, top), (width, height))` relative to the frame dimensions,
+    /// e.g. `((0.0, 0.0), (0.5, 1.0))` draws the left half. The portion is drawn where it would appear if the
+    /// entire frame was drawn. Defaults to `((0.0, 0.0), (1.0, 1.0))`.
+    pub fn crop<T>(mut self: Self, rect: T) -> Self where Rect: From<T> {
+        self.crop = Rect::from(rect);
+        self
+    }
+    /// Sets horizontal and vertical skew factors. A horizontal skew of 1.0 shifts each row of the frame to the right by
+    /// its vertical distance from the anchor. Skewing is applied after scaling and before rotation.
+    pub fn skew<T>(mut self: Self, skew: T) -> Self where Point2: From<T> {
+        self.skew = Point2::from(skew);
+        self
+    }
+    /// Sets the sort key used by layers with a [`LayerSortMode`](../enum.LayerSortMode.html) other than
+    /// `Insertion`. Defaults to 0.0.
+    pub fn z(mut self: Self, z: f32) -> Self {
+        self.z = z;
+        self
+    }
+    /// Draws the sprite.
+    pub fn draw(self: Self) {
+        self.sprite.draw_from_builder(self.layer, &self);
+    }
+    // Creates a new SpriteDrawBuilder instance.
+    pub(crate) fn new(sprite: &'a Sprite, layer: &'a Layer, frame_id: u32) -> SpriteDrawBuilder<'a> {
+        SpriteDrawBuilder {
+            sprite      : sprite,
+            layer       : layer,
+            frame_id    : frame_id,
+            position    : (0.0, 0.0),
+            colors      : [ Color::WHITE; 4 ],
+            component   : None,
+            rotation    : 0.0,
+            scale       : (1.0, 1.0),
+            anchor      : None,
+            flip        : (false, false),
+            crop        : ((0.0, 0.0), (1.0, 1.0)),
+            skew        : (0.0, 0.0),
+            z           : 0.0,
+        }
+    }
+}
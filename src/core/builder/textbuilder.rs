@@ -0,0 +1,121 @@
+use core::{Font, RichText, Layer, Color, HorizontalAlign, VerticalAlign};
+use core::math::*;
+
+/// A text writing builder.
+///
+/// Obtained from [`Font::write_builder()`](../struct.Font.html#method.write_builder) or
+/// [`RichText::write_builder()`](../struct.RichText.html#method.write_builder).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use radiant_rs::*;
+/// # let display = Display::builder().hidden().build().unwrap();
+/// # let layer = Layer::new((640., 480.));
+/// # let font = Font::builder(display.context()).family("Arial").size(16.0).build().unwrap();
+/// // a menu item centered on the screen
+/// font.write_builder(&layer, "Start game")
+///     .position((320., 240.))
+///     .align(HorizontalAlign::Center)
+///     .valign(VerticalAlign::Middle)
+///     .write();
+///
+/// // a score counter in the top right corner
+/// font.write_builder(&layer, "12500")
+///     .position((630., 10.))
+///     .align(HorizontalAlign::Right)
+///     .color(Color::YELLOW)
+///     .write();
+/// ```
+#[must_use]
+#[derive(Clone)]
+pub struct TextBuilder<'a> {
+    layer                   : &'a Layer,
+    pub(crate) source       : TextSource<'a>,
+    pub(crate) position     : Point2,
+    pub(crate) color        : Color,
+    pub(crate) max_width    : f32,
+    pub(crate) rotation     : f32,
+    pub(crate) scale        : Point2,
+    pub(crate) align        : HorizontalAlign,
+    pub(crate) valign       : VerticalAlign,
+    pub(crate) z            : f32,
+}
+
+impl<'a> TextBuilder<'a> {
+    /// Sets the position on the layer the text is aligned to. Defaults to (0.0, 0.0).
+    pub fn position<T>(mut self: Self, position: T) -> Self where Point2: From<T> {
+        self.position = Point2::from(position);
+        self
+    }
+    /// Sets a color for writing. Defaults to white. For rich text, the color is multiplied with the colors of the spans.
+    pub fn color(mut self: Self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+    /// Breaks lines after given width in pixels. Defaults to 0.0, which disables wrapping.
+    pub fn max_width(mut self: Self, max_width: f32) -> Self {
+        self.max_width = max_width;
+        self
+    }
+    /// Sets the rotation in radians around the position. Defaults to 0.0.
+    pub fn rotation(mut self: Self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+    /// Sets the scaling factor. Defaults to (1.0, 1.0).
+    pub fn scale<T>(mut self: Self, scale: T) -> Self where Point2: From<T> {
+        self.scale = Point2::from(scale);
+        self
+    }
+    /// Sets the horizontal alignment of each line relative to the position. Defaults to `HorizontalAlign::Left`.
+    pub fn align(mut self: Self, align: HorizontalAlign) -> Self {
+        self.align = align;
+        self
+    }
+    /// Sets the vertical alignment of the text relative to the position. Defaults to `VerticalAlign::Top`.
+    pub fn valign(mut self: Self, valign: VerticalAlign) -> Self {
+        self.valign = valign;
+        self
+    }
+    /// Sets the sort key used by layers with a [`LayerSortMode`](../enum.LayerSortMode.html) other than
+    /// `Insertion`. Defaults to 0.0.
+    pub fn z(mut self: Self, z: f32) -> Self {
+        self.z = z;
+        self
+    }
+    /// Writes the text.
+    pub fn write(self: Self) {
+        Font::write_from_builder(self.layer, &self);
+    }
+    // Creates a new TextBuilder instance.
+    pub(crate) fn new(font: &'a Font, layer: &'a Layer, text: &'a str) -> TextBuilder<'a> {
+        Self::from_source(TextSource::Plain(font, text), layer)
+    }
+    // Creates a new TextBuilder instance for rich text.
+    pub(crate) fn from_rich_text(text: &'a RichText, layer: &'a Layer) -> TextBuilder<'a> {
+        Self::from_source(TextSource::Rich(text), layer)
+    }
+    // Creates a new TextBuilder instance for given source.
+    fn from_source(source: TextSource<'a>, layer: &'a Layer) -> TextBuilder<'a> {
+        TextBuilder {
+            layer       : layer,
+            source      : source,
+            position    : (0.0, 0.0),
+            color       : Color::WHITE,
+            max_width   : 0.0,
+            rotation    : 0.0,
+            scale       : (1.0, 1.0),
+            align       : HorizontalAlign::Left,
+            valign      : VerticalAlign::Top,
+            z           : 0.0,
+        }
+    }
+}
+
+/// The text written by a TextBuilder.
+#[derive(Clone)]
+pub(crate) enum TextSource<'a> {
+    Plain(&'a Font, &'a str),
+    Rich(&'a RichText),
+}
@@ -1,10 +1,16 @@
-use core::{self, font, SpriteData, Vertex};
+use core::{self, font, SpriteData, Vertex, Atlas, SpriteStorage, Rect};
 use prelude::*;
 use std::default::Default;
 use backends::backend;
 
-/// Number of texture buckets. Also requires change to renderer.rs at "let uniforms = uniform! { ... }"
-pub const NUM_BUCKETS: usize = 6;
+/// Number of texture buckets. Bucket 0 is the font cache, buckets 1 to 9 hold frames of up to 16x16 to 4096x4096 pixels.
+/// Frames are further limited to the backend's maximum texture size.
+pub const NUM_BUCKETS: usize = 10;
+
+/// Sampler uniform names of the texture buckets. The sprite shader template is expanded from these.
+pub const BUCKET_SAMPLERS: [&'static str; NUM_BUCKETS] = [
+    "_rd_tex", "_rd_tex1", "_rd_tex2", "_rd_tex3", "_rd_tex4", "_rd_tex5", "_rd_tex6", "_rd_tex7", "_rd_tex8", "_rd_tex9",
+];
 
 /// Initial sprite capacity. Automatically increases.
 pub const INITIAL_CAPACITY: usize = 512;
@@ -39,6 +45,17 @@ impl Context {
     pub fn prune(self: &Self) {
         self.lock().prune();
     }
+    /// Sets how frames of subsequently created sprites are stored. Defaults to `SpriteStorage::Buckets`.
+    ///
+    /// In `SpriteStorage::Atlas` mode, frames are packed into shared pages instead of being padded to
+    /// the next power of two, greatly reducing memory use for odd frame sizes.
+    pub fn set_sprite_storage(self: &Self, storage: SpriteStorage) {
+        self.lock().sprite_storage = storage;
+    }
+    /// Returns the storage mode for newly created sprites.
+    pub fn sprite_storage(self: &Self) -> SpriteStorage {
+        self.lock().sprite_storage
+    }
     /// Mutex-locks the instance and returns the MutexGuard
     pub(crate) fn lock<'a>(self: &'a Self) -> MutexGuard<'a, ContextData> {
         self.0.lock().unwrap()
@@ -69,7 +86,7 @@ impl SpriteBackRef {
     /// Returns the texture id-range used by the referenced sprite or None, if it dropped.
     fn range(self: &Self) -> Option<(usize, usize)> {
         if let Some(data) = self.upgrade() {
-            Some((data.texture_id.load(Ordering::Relaxed), data.num_frames as usize * data.components as usize))
+            data.frames.first().map(|frame| (frame.texture_id.load(Ordering::Relaxed), data.num_frames as usize * data.components as usize))
         } else {
             None
         }
@@ -163,9 +180,12 @@ impl RawFrameArray {
             self.update(context);
             // Update sprite texture ids and generation.
             self.prune_sprites(|sprite| {
-                let texture_id = sprite.texture_id.load(Ordering::Relaxed);
+                let texture_id = sprite.frames.first().map_or(0, |frame| frame.texture_id.load(Ordering::Relaxed));
                 if let Some(new_texture_id) = destination_map.get(&texture_id) {
-                    sprite.texture_id.store(*new_texture_id, Ordering::Relaxed);
+                    for frame in sprite.frames.iter() {
+                        let frame_texture_id = frame.texture_id.load(Ordering::Relaxed);
+                        frame.texture_id.store(frame_texture_id - texture_id + *new_texture_id, Ordering::Relaxed);
+                    }
                 }
                 sprite.generation.store(generation, Ordering::Relaxed);
             });
@@ -182,6 +202,8 @@ impl RawFrameArray {
 pub struct ContextData {
     pub backend_context     : Option<backend::Context>,
     pub tex_arrays          : Vec<RawFrameArray>,
+    pub atlas               : Option<Atlas>,
+    pub sprite_storage      : SpriteStorage,
     pub font_cache_dimensions: u32,
     pub font_cache          : font::FontCache,
     pub font_texture        : Option<backend::Texture2d>,
@@ -202,6 +224,8 @@ impl ContextData {
             self.tex_arrays.push(RawFrameArray::new(&backend_context));
         }
 
+        self.atlas = Some(Atlas::new(&backend_context));
+
         // font cache texture
 
         let data = core::RawFrame {
@@ -225,6 +249,8 @@ impl ContextData {
         ContextData {
             backend_context     : None,
             tex_arrays          : Vec::new(),
+            atlas               : None,
+            sprite_storage      : SpriteStorage::default(),
             font_cache          : font::FontCache::new(font_cache_dimensions, font_cache_dimensions, 0.01, 0.01),
             font_texture        : None,
             font_cache_dimensions,
@@ -243,6 +269,12 @@ impl ContextData {
         self.init_backend(&display);
     }
 
+    /// Returns the maximum texture width and height supported by the backend. Without a backend only the texture
+    /// buckets limit the size.
+    pub fn max_texture_size(self: &Self) -> u32 {
+        self.backend_context.as_ref().map_or(u32::max_value(), |backend_context| backend_context.max_texture_size())
+    }
+
     /// Returns the context's generation.
     pub fn generation(self: &Self) -> usize {
         self.generation
@@ -258,6 +290,7 @@ impl ContextData {
         for ref mut array in self.tex_arrays.iter_mut() {
             array.update(self.backend_context.as_ref().unwrap());
         }
+        self.atlas.as_mut().unwrap().update(self.backend_context.as_ref().unwrap());
     }
 
     /// Store given frames to texture arrays
@@ -265,9 +298,18 @@ impl ContextData {
         self.tex_arrays[bucket_id as usize].store_frames(raw_frames)
     }
 
+    /// Packs given frames into the atlas. Returns texture id and uv rectangle for each frame.
+    pub fn store_atlas_frames(self: &mut Self, raw_frames: Vec<RawFrame>, components: u32, padding: u32) -> core::Result<Vec<(u32, Rect)>> {
+        self.atlas.as_mut().unwrap().store_frames(raw_frames, components, padding)
+    }
+
     /// Stores a weak sprite reference in the context so that the sprite's texture_id can be updated after a cleanup.
     pub fn store_sprite(self: &mut Self, bucket_id: u32, sprite_data: Weak<SpriteData>) {
-        self.tex_arrays[bucket_id as usize].store_sprite(sprite_data);
+        if bucket_id == core::ATLAS_BUCKET {
+            self.atlas.as_mut().unwrap().store_sprite(sprite_data);
+        } else {
+            self.tex_arrays[bucket_id as usize].store_sprite(sprite_data);
+        }
     }
 
     /// Prunes no longer used textures for all texture arrays.
@@ -276,6 +318,7 @@ impl ContextData {
         for array in self.tex_arrays.iter_mut() {
             array.prune(self.backend_context.as_ref().unwrap(), self.generation);
         }
+        self.atlas.as_mut().unwrap().prune(self.backend_context.as_ref().unwrap(), self.generation);
     }
 
     /// creates a single rectangle vertex buffer
@@ -2,6 +2,7 @@ use prelude::*;
 use core::*;
 use core::builder::*;
 use backends::backend;
+use image;
 
 /// A target to render to, e.g. a window or full screen.
 #[derive(Clone)]
@@ -11,6 +12,7 @@ pub struct Display {
     pub(crate) frame: Rc<RefCell<Option<backend::Frame>>>,
     pub(crate) input_data: Arc<RwLock<InputData>>,
     pub(crate) fullscreen: Rc<RefCell<Option<Monitor>>>,
+    pub(crate) recorder: Rc<RefCell<Option<Recorder>>>,
 }
 
 impl Debug for Display {
@@ -109,6 +111,13 @@ impl Display {
 
     /// Swaps current drawing frame with visible frame.
     pub fn swap_frame(self: &Self) {
+        if let Some(ref mut recorder) = *self.recorder.borrow_mut() {
+            if recorder.error.is_none() && self.frame.borrow().is_some() {
+                if let Err(error) = recorder.capture(|| self.read_pixels()) {
+                    recorder.error = Some(error);
+                }
+            }
+        }
         let frame = mem::replace(&mut *self.frame.borrow_mut(), None);
         if let Some(frame) = frame {
             frame.finish();
@@ -117,6 +126,53 @@ impl Display {
         }
     }
 
+    /// Reads the contents of the current frame back from the GPU. The first row of the returned image is the top of the frame.
+    ///
+    /// Must be called between [`prepare_frame()`](#method.prepare_frame) and [`swap_frame()`](#method.swap_frame).
+    pub fn read_pixels(self: &Self) -> image::RgbaImage {
+        let dimensions = self.frame.borrow().as_ref().expect(NO_FRAME_PREPARED).dimensions();
+        self.read_rect(((0, 0), dimensions))
+    }
+
+    /// Reads the given rectangle of the current frame back from the GPU. See [`read_pixels()`](#method.read_pixels).
+    ///
+    /// This uses integral pixel coordinates (top/left = 0/0). The rectangle must be entirely contained within the frame.
+    pub fn read_rect<T>(self: &Self, rect: T) -> image::RgbaImage where Rect<u32>: From<T> {
+        let rect: Rect<u32> = rect.into();
+        let ((left, top), (width, height)) = rect;
+        let dimensions = self.dimensions();
+        assert!(left + width <= dimensions.0 && top + height <= dimensions.1, "Rectangle exceeds display dimensions.");
+        let texture = Texture::builder(&self.context).dimensions((width, height)).format(TextureFormat::U8U8U8U8).build().unwrap();
+        let source_rect = ((left as i32, top as i32), (width as i32, height as i32));
+        let target_rect = ((0, 0), (width as i32, height as i32));
+        self.frame(|frame| texture.handle.copy_rect_from_frame(frame, source_rect, target_rect, TextureFilter::Nearest));
+        texture.read_pixels()
+    }
+
+    /// Writes the contents of the current frame to given PNG file.
+    ///
+    /// Must be called between [`prepare_frame()`](#method.prepare_frame) and [`swap_frame()`](#method.swap_frame).
+    pub fn screenshot(self: &Self, file: &str) -> Result<()> {
+        self.read_pixels().save(file)?;
+        Ok(())
+    }
+
+    /// Attaches a recorder that captures frames on [`swap_frame()`](#method.swap_frame), replacing any
+    /// previously attached recorder.
+    pub fn start_recording(self: &Self, recorder: Recorder) {
+        *self.recorder.borrow_mut() = Some(recorder);
+    }
+
+    /// Detaches the current recorder and returns the number of files it has written or the first error
+    /// it encountered. Recording stops at the first error.
+    pub fn stop_recording(self: &Self) -> Result<u32> {
+        match self.recorder.borrow_mut().take() {
+            Some(Recorder { error: Some(error), .. }) => Err(error),
+            Some(recorder) => Ok(recorder.num_written()),
+            None => Ok(0),
+        }
+    }
+
     /// Enables cursor grab mode. While in this mode, the mouse cursor will be hidden and
     /// constrained to the window.
     ///
@@ -266,6 +322,7 @@ impl Display {
             frame       : Rc::new(RefCell::new(None)),
             input_data  : Arc::new(RwLock::new(InputData::new())),
             fullscreen  : Rc::new(RefCell::new(fullscreen)),
+            recorder    : Rc::new(RefCell::new(None)),
         })
     }
 
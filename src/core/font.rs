@@ -7,6 +7,36 @@ use font_loader::system_fonts;
 
 static FONT_COUNTER: AtomicUsize = ATOMIC_USIZE_INIT;
 
+/// Invisible hyphenation hint, shown as a hyphen when a line is wrapped after it.
+const SOFT_HYPHEN: char = '\u{ad}';
+
+/// Horizontal alignment of text relative to the position it is written at.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HorizontalAlign {
+    /// Lines start at the position.
+    Left,
+    /// Lines are centered on the position.
+    Center,
+    /// Lines end at the position.
+    Right,
+    /// Lines start at the position. Lines ended by wrapping are stretched to the maximum width by widening their
+    /// spaces. Falls back to left alignment if no maximum width is set.
+    Justify,
+}
+
+/// Vertical alignment of text relative to the position it is written at.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum VerticalAlign {
+    /// The top of the first line is at the position.
+    Top,
+    /// The text is centered on the position.
+    Middle,
+    /// The baseline of the first line is at the position.
+    Baseline,
+    /// The bottom of the last line is at the position.
+    Bottom,
+}
+
 /// A font used for writing on a [`Layer`](struct.Layer.html).
 ///
 /// Use [`Font::builder()`](#method.builder) to create a new font from a registered system font or
@@ -15,9 +45,12 @@ static FONT_COUNTER: AtomicUsize = ATOMIC_USIZE_INIT;
 /// In addition to the usual properties of a font, radiant also assigns a fixed size
 /// to each font object. Instead of modifying this value, you can clone a new font
 /// with a different size using [`Font::with_size()`](struct.Font.html#method.with_size).
+///
+/// The font is parsed once on creation. Clones and differently sized copies share the parsed font and its cached
+/// glyph metrics, so they are cheap to create.
 #[derive(Clone)]
 pub struct Font {
-    data    : Vec<u8>,
+    data    : Arc<FontData>,
     font_id : usize,
     size    : f32,
     context : Context,
@@ -26,7 +59,7 @@ pub struct Font {
 impl Debug for Font {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Font")
-            .field("data_len", &self.data.len())
+            .field("glyph_count", &self.data.font.glyph_count())
             .field("font_id", &self.font_id)
             .field("size", &self.size)
             .finish()
@@ -56,7 +89,7 @@ impl Font {
         let mut f = File::open(Path::new(file))?;
         let mut font_data = Vec::new();
         f.read_to_end(&mut font_data)?;
-        Ok(Self::create(context, font_data, 12.0))
+        Self::create(context, font_data, 12.0)
     }
 
     /// Returns the names of all available system fonts.
@@ -86,14 +119,14 @@ impl Font {
     /// Write to given layer.
     pub fn write<T>(self: &Self, layer: &Layer, text: &str, position: T, color: Color) -> &Font where Point2<f32>: From<T> {
         let position = Point2::from(position);
-        self.write_paragraph(layer, text, position.0, position.1, 0.0, color, 0.0, 1.0, 1.0);
+        self.write_paragraph(layer, text, position.0, position.1, 0.0, color, 0.0, 1.0, 1.0, HorizontalAlign::Left, VerticalAlign::Top);
         self
     }
 
     /// Write to given layer. Breaks lines after max_width pixels.
     pub fn write_wrapped<T>(self: &Self, layer: &Layer, text: &str, position: T, color: Color, max_width: f32) -> &Font where Point2<f32>: From<T> {
         let position = Point2::from(position);
-        self.write_paragraph(layer, text, position.0, position.1, max_width, color, 0.0, 1.0, 1.0);
+        self.write_paragraph(layer, text, position.0, position.1, max_width, color, 0.0, 1.0, 1.0, HorizontalAlign::Left, VerticalAlign::Top);
         self
     }
 
@@ -101,10 +134,52 @@ impl Font {
     pub fn write_transformed<T, U>(self: &Self, layer: &Layer, text: &str, position: T, color: Color, max_width: f32, rotation: f32, scale: U) -> &Font where Point2<f32>: From<T>+From<U> {
         let position = Point2::from(position);
         let scale = Point2::from(scale);
-        self.write_paragraph(layer, text, position.0, position.1, max_width, color, rotation, scale.0, scale.1);
+        self.write_paragraph(layer, text, position.0, position.1, max_width, color, rotation, scale.0, scale.1, HorizontalAlign::Left, VerticalAlign::Top);
         self
     }
 
+    /// Writes text configured by given text writing builder to the layer.
+    pub(crate) fn write_from_builder(layer: &Layer, builder: &TextBuilder) {
+        let runs = match builder.source {
+            TextSource::Plain(font, text) => vec![ (font, builder.color, text) ],
+            TextSource::Rich(text) => text.runs(builder.color),
+        };
+        Self::write_runs(layer, &runs, builder.position, builder.max_width, builder.rotation, builder.scale, builder.align, builder.valign, builder.z);
+    }
+
+    /// Returns a [text writing builder](support/struct.TextBuilder.html) for writing given text onto the given layer
+    /// with additional options like alignment, rotation and scaling.
+    pub fn write_builder<'b>(self: &'b Self, layer: &'b Layer, text: &'b str) -> TextBuilder<'b> {
+        TextBuilder::new(self, layer, text)
+    }
+
+    /// Measures given text without drawing it. Breaks lines after max_width pixels unless max_width is 0.0.
+    ///
+    /// The text is laid out exactly as by [`write_wrapped()`](#method.write_wrapped). Coordinates are relative to the
+    /// position the text would be written at and do not include the rotation or scaling of
+    /// [`write_transformed()`](#method.write_transformed).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use radiant_rs::*;
+    /// # let display = Display::builder().hidden().build().unwrap();
+    /// # let layer = Layer::new((640., 480.));
+    /// # let font = Font::builder(display.context()).family("Arial").size(16.0).build().unwrap();
+    /// // center a label horizontally
+    /// let metrics = font.measure("Game Over", 0.0);
+    /// font.write(&layer, "Game Over", (320. - (metrics.bounds.1).0 / 2., 200.), Color::WHITE);
+    /// ```
+    pub fn measure(self: &Self, text: &str, max_width: f32) -> TextMetrics {
+        Self::measure_runs(&[ (self, Color::WHITE, text) ], max_width)
+    }
+
+    /// Measures given text without drawing it and returns the rectangle occupied by each character. Breaks lines
+    /// after max_width pixels unless max_width is 0.0. See [`measure()`](#method.measure).
+    pub fn measure_glyphs(self: &Self, text: &str, max_width: f32) -> Vec<TextGlyph> {
+        Self::measure_run_glyphs(&[ (self, Color::WHITE, text) ], max_width)
+    }
+
     /// Returns the font wrapped in an std::Arc.
     pub fn arc(self: Self) -> Arc<Self> {
         Arc::new(self)
@@ -119,92 +194,209 @@ impl Font {
     pub(crate) fn from_info(context: &Context, info: FontInfo) -> core::Result<Font> {
 
         if let Some((font_data, _)) = system_fonts::get(&Self::build_property(&info)) {
-            Ok(Self::create(context, font_data, info.size))
+            Self::create(context, font_data, info.size)
         } else {
             Err(core::Error::FontError("Failed to get system font".to_string()))
         }
     }
 
     /// Creates a new unique font
-    fn create(context: &Context, font_data: Vec<u8>, size: f32) -> Font {
-        Font {
-            data    : font_data,
+    fn create(context: &Context, font_data: Vec<u8>, size: f32) -> core::Result<Font> {
+        Ok(Font {
+            data    : Arc::new(FontData::new(font_data)?),
             font_id : FONT_COUNTER.fetch_add(1, Ordering::Relaxed),
             size    : size,
             context : context.clone(),
-        }
+        })
     }
 
     /// Write text to given layer using given font
-    fn write_paragraph(self: &Self, layer: &Layer, text: &str, x: f32, y: f32, max_width: f32, color: Color, rotation: f32, scale_x: f32, scale_y: f32) {
+    fn write_paragraph(self: &Self, layer: &Layer, text: &str, x: f32, y: f32, max_width: f32, color: Color, rotation: f32, scale_x: f32, scale_y: f32, align: HorizontalAlign, valign: VerticalAlign) {
+        Self::write_runs(layer, &[ (self, color, text) ], (x, y), max_width, rotation, (scale_x, scale_y), align, valign, 0.0);
+    }
 
-        // !todo probably expensive, but rusttype is completely opaque. would be nice to be able to store Font::info outside of a "may or may not own" container
-        let rt_font = rusttype::FontCollection::from_bytes(&self.data[..]).unwrap().into_font().unwrap();
+    /// Measures runs of text given as font, color and text.
+    pub(crate) fn measure_runs(runs: &[(&Font, Color, &str)], max_width: f32) -> TextMetrics {
+        let paragraph = Self::layout_paragraph(&Self::layout_runs(runs), max_width);
+        let width = paragraph.line_widths.iter().fold(0.0f32, |width, &line_width| width.max(line_width));
+        let height = paragraph.height();
+        let line_height = runs.first().map_or(0.0, |&(font, _, _)| {
+            let v_metrics = font.data.font.v_metrics(rusttype::Scale::uniform(font.size));
+            v_metrics.ascent - v_metrics.descent + v_metrics.line_gap
+        });
+        TextMetrics {
+            bounds      : ((0.0, 0.0), (width, height)),
+            line_count  : paragraph.line_widths.len(),
+            line_widths : paragraph.line_widths,
+            line_height : line_height,
+        }
+    }
 
-        let bucket_id = 0;
-        let glyphs = Self::layout_paragraph(&rt_font, rusttype::Scale::uniform(self.size), max_width, &text);
-        let context = self.context.lock();
+    /// Measures the characters of runs of text given as font, color and text.
+    pub(crate) fn measure_run_glyphs(runs: &[(&Font, Color, &str)], max_width: f32) -> Vec<TextGlyph> {
+        let paragraph = Self::layout_paragraph(&Self::layout_runs(runs), max_width);
+        paragraph.glyphs.iter().zip(paragraph.characters.iter()).map(|(glyph, &(character, line))| {
+            let (baseline, ascent, descent) = paragraph.lines[line];
+            TextGlyph {
+                character   : character,
+                line        : line,
+                rect        : ((glyph.position().x, baseline - ascent), (glyph.unpositioned().h_metrics().advance_width, ascent - descent)),
+            }
+        }).collect()
+    }
 
-        context.font_cache.queue(self.font_id, &glyphs);
+    /// Writes runs of text given as font, color and text to given layer. All fonts are expected to belong to the same
+    /// context. The glyphs are added as a single draw call.
+    pub(crate) fn write_runs(layer: &Layer, runs: &[(&Font, Color, &str)], position: Point2, max_width: f32, rotation: f32, scale: Point2, align: HorizontalAlign, valign: VerticalAlign, z: f32) {
 
+        if runs.len() == 0 {
+            return;
+        }
+
+        let bucket_id = 0;
+        let paragraph = Self::layout_paragraph(&Self::layout_runs(runs), max_width);
+        let (offsets_x, offset_y) = paragraph.alignment(align, valign, max_width);
+        let context = runs[0].0.context.lock();
+
+        // queue the glyphs of all runs before caching so that they are all available at the same time
+        let mut start = 0;
+        while start < paragraph.glyphs.len() {
+            let run = paragraph.runs[start];
+            let end = start + paragraph.runs[start..].iter().take_while(|&&glyph_run| glyph_run == run).count();
+            context.font_cache.queue(runs[run].0.font_id, &paragraph.glyphs[start..end]);
+            start = end;
+        }
+        context.font_cache.cache_queued();
+
+        let (x, y) = position;
         let anchor = (0., 0.);
-        let scale = (scale_x, scale_y);
         let cos_rot = rotation.cos();
         let sin_rot = rotation.sin();
+        let mut quads = Vec::with_capacity(paragraph.glyphs.len());
 
-        for glyph in &glyphs {
-            if let Some((uv, pos, dim)) = context.font_cache.rect_for(self.font_id, glyph) {
-                let dist_x = pos.0 * scale_x;
-                let dist_y = pos.1 * scale_y;
+        for ((glyph, &run), offset_x) in paragraph.glyphs.iter().zip(paragraph.runs.iter()).zip(offsets_x) {
+            let (font, color, _) = runs[run];
+            if let Some((uv, pos, dim)) = context.font_cache.rect_for(font.font_id, glyph) {
+                let dist_x = (pos.0 + offset_x) * scale.0;
+                let dist_y = (pos.1 + offset_y) * scale.1;
                 let offset_x = x + dist_x * cos_rot - dist_y * sin_rot;
                 let offset_y = y + dist_x * sin_rot + dist_y * cos_rot;
-                layer.add_rect(None, bucket_id, 0, 1, uv, (offset_x, offset_y), anchor, dim, color, rotation, scale);
+                let mut vertices = Layer::rect_vertices(bucket_id, 0, 1, uv, (offset_x, offset_y), anchor, dim, color, rotation, scale);
+                for vertex in vertices.iter_mut() {
+                    vertex.z = z;
+                }
+                quads.push(vertices);
             }
         }
+
+        layer.add_quads(None, &quads);
+    }
+
+    /// Combines runs of text with their parsed fonts for layouting.
+    fn layout_runs<'a, 'b>(runs: &[(&'a Font, Color, &'b str)]) -> Vec<(&'a FontData, rusttype::Scale, &'b str)> {
+        runs.iter().map(|&(font, _, text)| (&*font.data, rusttype::Scale::uniform(font.size), text)).collect()
     }
 
-    /// Layout a paragraph of glyphs
-    fn layout_paragraph<'a>(font: &'a rusttype::Font, scale: rusttype::Scale, width: f32, text: &str) -> Vec<rusttype::PositionedGlyph<'a>> {
+    /// Layout a paragraph of glyphs from runs of text given as font, scale and text. Lines are wrapped at the line
+    /// break opportunities defined by the Unicode line breaking algorithm (UAX #14). Words that exceed a line on
+    /// their own are broken at any character.
+    fn layout_paragraph<'a>(runs: &[(&'a FontData, rusttype::Scale, &str)], width: f32) -> Paragraph<'a> {
 
         use unicode_normalization::UnicodeNormalization;
-        let mut result = Vec::new();
-        let v_metrics = font.v_metrics(scale);
-        let advance_height = v_metrics.ascent - v_metrics.descent + v_metrics.line_gap;
-        let mut caret = rusttype::point(0.0, v_metrics.ascent);
-        let mut last_glyph_id = None;
-
-        for c in text.nfc() {
-            if c.is_control() {
-                match c {
-                    '\n' => {
-                        caret = rusttype::point(0.0, caret.y + advance_height);
-                    },
-                    _ => {}
+        use xi_unicode::LineBreakIterator;
+
+        // normalized text of all runs and the offset each run starts at
+        let mut text = String::new();
+        let mut run_starts = Vec::with_capacity(runs.len());
+        for &(_, _, run_text) in runs {
+            run_starts.push(text.len());
+            text.extend(run_text.nfc());
+        }
+
+        let run_at = |offset: usize| run_starts.iter().rposition(|&start| start <= offset).unwrap();
+        let v_metrics = |run: usize| runs[run].0.font.v_metrics(runs[run].1);
+
+        // advance width of a single line of text starting at given offset
+        let text_width = |text: &str, offset: usize, mut last_glyph: Option<(usize, rusttype::GlyphId)>| {
+            let mut width = 0.0;
+            for (index, c) in text.char_indices().filter(|&(_, c)| !c.is_control() && c != SOFT_HYPHEN) {
+                let run = run_at(offset + index);
+                let (font, scale, _) = runs[run];
+                let (id, advance_width) = font.glyph(c, scale);
+                if let Some((last_run, last_id)) = last_glyph {
+                    if last_run == run {
+                        width += font.pair_kerning(last_id, id, scale);
+                    }
                 }
-                continue;
+                last_glyph = Some((run, id));
+                width += advance_width;
             }
-
-            let base_glyph = font.glyph(c);
-
-            if let Some(id) = last_glyph_id.take() {
-                caret.x += font.pair_kerning(scale, id, base_glyph.id());
+            width
+        };
+
+        let mut result = ParagraphBuilder::new(if runs.len() > 0 { v_metrics(0) } else { rusttype::VMetrics { ascent: 0.0, descent: 0.0, line_gap: 0.0 } });
+        let mut caret_x = 0.0;
+        let mut last_glyph = None;
+        let mut soft_hyphen: Option<usize> = None;
+        let mut start = 0;
+
+        for (end, _) in LineBreakIterator::new(&text) {
+            let segment = &text[start..end];
+            let segment_start = start;
+            start = end;
+
+            // wrap before segments that don't fit, ignoring trailing whitespace. a soft hyphen at the end of the
+            // previous segment becomes visible
+            if width > 0.0 && caret_x > 0.0 {
+                let word = segment.trim_right_matches(|c: char| c.is_whitespace() || c == SOFT_HYPHEN);
+                if caret_x + text_width(word, segment_start, last_glyph) > width {
+                    if let Some(run) = soft_hyphen {
+                        let (font, scale, _) = runs[run];
+                        let (id, advance_width) = font.glyph('-', scale);
+                        result.push_glyph(font.font.glyph(id).scaled(scale), advance_width, SOFT_HYPHEN, run, v_metrics(run), caret_x);
+                    }
+                    result.break_line(true, v_metrics(run_at(segment_start)));
+                    caret_x = 0.0;
+                    last_glyph = None;
+                }
             }
+            soft_hyphen = None;
 
-            last_glyph_id = Some(base_glyph.id());
-            let mut glyph = base_glyph.scaled(scale).positioned(caret);
+            for (index, c) in segment.char_indices() {
+                let run = run_at(segment_start + index);
+                if c == SOFT_HYPHEN {
+                    soft_hyphen = Some(run);
+                    continue;
+                }
+                if c.is_control() {
+                    continue;
+                }
 
-            if let Some(bb) = glyph.pixel_bounding_box() {
-                if width > 0.0 && bb.max.x > width as i32 {
-                    caret = rusttype::point(0.0, caret.y + advance_height);
-                    glyph = glyph.into_unpositioned().positioned(caret);
-                    last_glyph_id = None;
+                let (font, scale, _) = runs[run];
+                let (id, advance_width) = font.glyph(c, scale);
+                let mut kerning = match last_glyph {
+                    Some((last_run, last_id)) if last_run == run => font.pair_kerning(last_id, id, scale),
+                    _ => 0.0,
+                };
+
+                if width > 0.0 && caret_x > 0.0 && !c.is_whitespace() && caret_x + kerning + advance_width > width {
+                    result.break_line(true, v_metrics(run));
+                    caret_x = 0.0;
+                    kerning = 0.0;
                 }
+
+                last_glyph = Some((run, id));
+                caret_x = result.push_glyph(font.font.glyph(id).scaled(scale), advance_width, c, run, v_metrics(run), caret_x + kerning);
             }
 
-            caret.x += glyph.unpositioned().h_metrics().advance_width;
-            result.push(glyph);
+            // mandatory breaks. \r\n is a single break
+            if segment.ends_with(is_line_break) {
+                result.break_line(false, v_metrics(run_at(end - 1)));
+                caret_x = 0.0;
+                last_glyph = None;
+            }
         }
-        result
+        result.finish()
     }
 
     /// Builds a FontProperty for the underlying system_fonts library
@@ -229,6 +421,228 @@ impl Font {
     }
 }
 
+/// Measurements of a paragraph of text, returned by [`Font::measure()`](../struct.Font.html#method.measure).
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextMetrics {
+    /// Bounding box `((x, y), (width, height))` of the text, relative to the position it is written at.
+    pub bounds      : Rect,
+    /// Number of lines, including those created by wrapping.
+    pub line_count  : usize,
+    /// Width of each line in pixels, excluding trailing whitespace.
+    pub line_widths : Vec<f32>,
+    /// Distance in pixels between the baselines of two lines. For rich text, this is the line height of the
+    /// first span's font. Lines containing larger text are spaced further apart.
+    pub line_height : f32,
+}
+
+/// The rectangle occupied by a character, returned by [`Font::measure_glyphs()`](../struct.Font.html#method.measure_glyphs).
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextGlyph {
+    /// The character.
+    pub character   : char,
+    /// Zero-based line the character was placed on.
+    pub line        : usize,
+    /// Rectangle `((x, y), (advance width, line height))` of the character, relative to the position the text is
+    /// written at.
+    pub rect        : Rect,
+}
+
+/// A parsed font, shared by all sizes of a font. Caches glyph ids, advance widths and kerning at a scale of 1.0.
+/// Cached values are looked up under a read lock, so threads laying out text concurrently only wait for each other
+/// when a value is missing.
+struct FontData {
+    font    : rusttype::Font<'static>,
+    glyphs  : RwLock<HashMap<char, (rusttype::GlyphId, f32)>>,
+    kerning : RwLock<HashMap<(u32, u32), f32>>,
+}
+
+impl FontData {
+
+    /// Parses given font data.
+    fn new(data: Vec<u8>) -> core::Result<FontData> {
+        let font = rusttype::FontCollection::from_bytes(data)
+            .and_then(|collection| collection.into_font())
+            .map_err(|error| core::Error::FontError(format!("Failed to parse font: {}", error)))?;
+        Ok(FontData {
+            font    : font,
+            glyphs  : RwLock::new(HashMap::new()),
+            kerning : RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Returns the id and advance width of the glyph for given character.
+    fn glyph(self: &Self, c: char, scale: rusttype::Scale) -> (rusttype::GlyphId, f32) {
+        let font = &self.font;
+        let (id, advance_width) = cached(&self.glyphs, c, || {
+            let glyph = font.glyph(c);
+            (glyph.id(), glyph.scaled(rusttype::Scale::uniform(1.0)).h_metrics().advance_width)
+        });
+        (id, advance_width * scale.x)
+    }
+
+    /// Returns the kerning between given glyphs.
+    fn pair_kerning(self: &Self, first: rusttype::GlyphId, second: rusttype::GlyphId, scale: rusttype::Scale) -> f32 {
+        let font = &self.font;
+        let pair_kerning = cached(&self.kerning, (first.0, second.0), || font.pair_kerning(rusttype::Scale::uniform(1.0), first, second));
+        pair_kerning * scale.x
+    }
+}
+
+/// Returns the value cached for given key, computing and caching it if it is missing.
+fn cached<K, V, F>(cache: &RwLock<HashMap<K, V>>, key: K, compute: F) -> V where K: Eq + ::std::hash::Hash, V: Copy, F: FnOnce() -> V {
+    if let Some(&value) = cache.read().unwrap().get(&key) {
+        return value;
+    }
+    *cache.write().unwrap().entry(key).or_insert_with(compute)
+}
+
+/// A laid out paragraph of text.
+struct Paragraph<'a> {
+    glyphs      : Vec<rusttype::PositionedGlyph<'a>>,
+    /// Character and line of each glyph.
+    characters  : Vec<(char, usize)>,
+    /// Run of each glyph.
+    runs        : Vec<usize>,
+    line_widths : Vec<f32>,
+    /// Whether each line was ended by wrapping.
+    wrapped     : Vec<bool>,
+    /// Baseline, ascent and descent of each line.
+    lines       : Vec<(f32, f32, f32)>,
+}
+
+impl<'a> Paragraph<'a> {
+
+    /// Returns the height of the paragraph.
+    fn height(self: &Self) -> f32 {
+        let &(baseline, _, descent) = self.lines.last().unwrap();
+        baseline - descent
+    }
+
+    /// Returns the horizontal offset of each glyph and the vertical offset of the paragraph for given alignment.
+    fn alignment(self: &Self, align: HorizontalAlign, valign: VerticalAlign, max_width: f32) -> (Vec<f32>, f32) {
+
+        let offset_y = match valign {
+            VerticalAlign::Top => 0.0,
+            VerticalAlign::Middle => -self.height() / 2.0,
+            VerticalAlign::Baseline => -self.lines[0].0,
+            VerticalAlign::Bottom => -self.height(),
+        };
+
+        if align == HorizontalAlign::Left || (align == HorizontalAlign::Justify && max_width <= 0.0) {
+            return (vec![ 0.0; self.glyphs.len() ], offset_y);
+        }
+
+        // number of spaces between words on each line
+        let mut spaces = vec![ 0; self.line_widths.len() ];
+        let mut pending_spaces = vec![ 0; self.line_widths.len() ];
+        for &(character, line) in self.characters.iter() {
+            if character.is_whitespace() {
+                pending_spaces[line] += 1;
+            } else {
+                spaces[line] += pending_spaces[line];
+                pending_spaces[line] = 0;
+            }
+        }
+
+        let mut spaces_before = vec![ 0; self.line_widths.len() ];
+        let offsets_x = self.characters.iter().map(|&(character, line)| {
+            let offset = match align {
+                HorizontalAlign::Center => -self.line_widths[line] / 2.0,
+                HorizontalAlign::Right => -self.line_widths[line],
+                _ if self.wrapped[line] && spaces[line] > 0 => {
+                    (max_width - self.line_widths[line]).max(0.0) / spaces[line] as f32 * spaces_before[line].min(spaces[line]) as f32
+                }
+                _ => 0.0,
+            };
+            if character.is_whitespace() {
+                spaces_before[line] += 1;
+            }
+            offset
+        }).collect();
+
+        (offsets_x, offset_y)
+    }
+}
+
+/// Accumulates the glyphs of a paragraph during layout.
+struct ParagraphBuilder<'a> {
+    /// Glyph and horizontal position.
+    glyphs      : Vec<(rusttype::ScaledGlyph<'a>, f32)>,
+    characters  : Vec<(char, usize)>,
+    runs        : Vec<usize>,
+    line_widths : Vec<f32>,
+    wrapped     : Vec<bool>,
+    /// Vertical metrics of each line and whether they were taken from a glyph on the line.
+    metrics     : Vec<(rusttype::VMetrics, bool)>,
+}
+
+impl<'a> ParagraphBuilder<'a> {
+
+    /// Creates a new builder. The first line uses given vertical metrics until a glyph is placed on it.
+    fn new(v_metrics: rusttype::VMetrics) -> Self {
+        ParagraphBuilder {
+            glyphs      : Vec::new(),
+            characters  : Vec::new(),
+            runs        : Vec::new(),
+            line_widths : vec![ 0.0 ],
+            wrapped     : vec![ false ],
+            metrics     : vec![ (v_metrics, false) ],
+        }
+    }
+
+    /// Places given glyph of given run on the current line and returns the caret position after it.
+    fn push_glyph(self: &mut Self, glyph: rusttype::ScaledGlyph<'a>, advance_width: f32, character: char, run: usize, v_metrics: rusttype::VMetrics, caret_x: f32) -> f32 {
+        let line = self.line_widths.len() - 1;
+        self.glyphs.push((glyph, caret_x));
+        self.characters.push((character, line));
+        self.runs.push(run);
+        if !character.is_whitespace() {
+            self.line_widths[line] = caret_x + advance_width;
+        }
+        // lines are as high as their largest glyph
+        let metrics = &mut self.metrics[line];
+        if metrics.1 {
+            metrics.0.ascent = metrics.0.ascent.max(v_metrics.ascent);
+            metrics.0.descent = metrics.0.descent.min(v_metrics.descent);
+            metrics.0.line_gap = metrics.0.line_gap.max(v_metrics.line_gap);
+        } else {
+            *metrics = (v_metrics, true);
+        }
+        caret_x + advance_width
+    }
+
+    /// Ends the current line. The next line uses given vertical metrics until a glyph is placed on it.
+    fn break_line(self: &mut Self, wrapped: bool, v_metrics: rusttype::VMetrics) {
+        *self.wrapped.last_mut().unwrap() = wrapped;
+        self.line_widths.push(0.0);
+        self.wrapped.push(false);
+        self.metrics.push((v_metrics, false));
+    }
+
+    /// Positions the glyphs on their lines' baselines and returns the paragraph.
+    fn finish(self: Self) -> Paragraph<'a> {
+        let mut lines = Vec::with_capacity(self.metrics.len());
+        let mut baseline = 0.0;
+        let mut previous: Option<rusttype::VMetrics> = None;
+        for &(v_metrics, _) in &self.metrics {
+            baseline += previous.map_or(0.0, |previous| previous.line_gap - previous.descent) + v_metrics.ascent;
+            lines.push((baseline, v_metrics.ascent, v_metrics.descent));
+            previous = Some(v_metrics);
+        }
+        let glyphs = self.glyphs.into_iter().zip(self.characters.iter()).map(|((glyph, x), &(_, line))| {
+            glyph.positioned(rusttype::point(x, lines[line].0))
+        }).collect();
+        Paragraph {
+            glyphs      : glyphs,
+            characters  : self.characters,
+            runs        : self.runs,
+            line_widths : self.line_widths,
+            wrapped     : self.wrapped,
+            lines       : lines,
+        }
+    }
+}
+
 /// A wrapper around rusttype's font cache.
 pub struct FontCache {
     cache   : Mutex<rusttype::gpu_cache::Cache<'static>>,
@@ -254,16 +668,20 @@ impl FontCache {
         }
     }
 
-    /// Queues a glyph for caching.
+    /// Queues glyphs for caching.
     pub fn queue(self: &Self, font_id: usize, glyphs: &[rusttype::PositionedGlyph]) {
-
         let mut cache = self.cache.lock().unwrap();
-        let mut queue = self.queue.lock().unwrap();
-        let mut dirties = false;
-
         for glyph in glyphs {
             cache.queue_glyph(font_id, glyph.standalone());
         }
+    }
+
+    /// Caches all queued glyphs.
+    pub fn cache_queued(self: &Self) {
+
+        let mut cache = self.cache.lock().unwrap();
+        let mut queue = self.queue.lock().unwrap();
+        let mut dirties = false;
 
         cache.cache_queued(|rect, data| {
             queue.push( ( ((rect.min.x, rect.min.y), (rect.max.x, rect.max.y)), data.to_vec() ) );
@@ -326,3 +744,11 @@ impl Default for FontInfo {
         }
    }
 }
+
+/// Returns whether given character forces a line break.
+fn is_line_break(c: char) -> bool {
+    match c {
+        '\n' | '\r' | '\u{b}' | '\u{c}' | '\u{85}' | '\u{2028}' | '\u{2029}' => true,
+        _ => false,
+    }
+}
@@ -1,10 +1,53 @@
 use prelude::*;
 use avec;
-use core::{blendmodes, BlendMode, context, Color, Program, Vertex};
+use core::{blendmodes, BlendMode, context, Color, Program, Sprite, Vertex};
 use core::math::*;
 
 static LAYER_COUNTER: AtomicUsize = ATOMIC_USIZE_INIT;
 
+/// Bucket id used for untextured geometry.
+pub const UNTEXTURED_BUCKET: u32 = 254;
+
+/// Bucket id used for geometry textured by the texture given to `Renderer::draw_layer_with_texture()`.
+pub const LAYER_TEXTURE_BUCKET: u32 = 253;
+
+/// Order in which a layer's contents are drawn. See [`Layer::set_sort_mode()`](struct.Layer.html#method.set_sort_mode).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LayerSortMode {
+    /// Contents are drawn in the order they were added to the layer.
+    Insertion,
+    /// Contents are drawn by ascending z value, e.g. set with [`SpriteDrawBuilder::z()`](support/struct.SpriteDrawBuilder.html#method.z)
+    /// or the `z` argument of primitives and meshes.
+    Z,
+    /// Contents are drawn by ascending vertical position, then by z value. Useful for isometric or top-down views
+    /// where objects lower on the screen are in front.
+    Y,
+}
+
+/// A vertex of a triangle mesh drawn with [`Layer::draw_mesh()`](struct.Layer.html#method.draw_mesh),
+/// [`Layer::draw_textured_mesh()`](struct.Layer.html#method.draw_textured_mesh) or
+/// [`Sprite::draw_mesh()`](struct.Sprite.html#method.draw_mesh).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MeshVertex {
+    /// Position relative to the mesh position.
+    pub position    : Point2,
+    /// Texture coordinates in the range 0.0 to 1.0. Ignored for untextured meshes.
+    pub uv          : Point2,
+    /// Vertex color. Colors are interpolated across each triangle.
+    pub color       : Color,
+}
+
+impl MeshVertex {
+    /// Creates a new mesh vertex.
+    pub fn new<T, U>(position: T, uv: U, color: Color) -> Self where Point2: From<T>, Point2: From<U> {
+        MeshVertex {
+            position    : Point2::from(position),
+            uv          : Point2::from(uv),
+            color       : color,
+        }
+    }
+}
+
 /// A drawing surface for text and sprites that implements send+sync and is wait-free for drawing operations.
 ///
 /// In radiant_rs, sprite drawing happens on layers. Layers provide transformation capabilities in
@@ -17,13 +60,28 @@ static LAYER_COUNTER: AtomicUsize = ATOMIC_USIZE_INIT;
 /// time before rendering.
 ///
 /// Drawing to a layer is a wait-free atomic operation that can be safely performed from multiple threads at
-/// the same time. Modifying layer properties like the matrices may cause other threads to wait.
+/// the same time. Modifying layer properties like the matrices may cause other threads to wait. Contents are drawn in
+/// insertion order unless a [sort mode](#method.set_sort_mode) is set.
+///
+/// In addition to sprites and text, layers accept untextured primitives like rectangles, lines, circles and
+/// convex polygons. The model matrix transforms primitives around their center. Their `z` argument orders them on
+/// [sorted](#method.set_sort_mode) layers.
+///
+/// Arbitrary triangle meshes can be drawn untextured, textured with a sprite frame or textured with a
+/// [`Texture`](struct.Texture.html) that is supplied when the layer is rendered. The model matrix transforms
+/// meshes around their position.
+///
+/// Layers created with [`Layer::retained()`](#method.retained) keep sprites added with
+/// [`add_sprite()`](#method.add_sprite) until they are removed. These sprites can be moved or changed through their
+/// [`SpriteHandle`](struct.SpriteHandle.html) without redrawing the layer and only changed sprites are uploaded
+/// to the GPU. Drawing onto retained layers and updating their sprites briefly locks the layer instead of being wait-free.
 #[derive(Debug)]
 pub struct Layer {
     view_matrix     : Mutex<Mat4Stack<f32>>,
     model_matrix    : Mutex<Mat4Stack<f32>>,
     blend           : Mutex<BlendMode>,
     color           : Mutex<Color>,
+    culling         : AtomicBool,
     contents        : Arc<LayerContents>,
     program         : Option<Program>,
 }
@@ -42,18 +100,72 @@ impl Clone for Layer {
 /// Layer contents, shared among layer clones.
 struct LayerContents {
     vertex_data     : avec::AVec<Vertex>,
+    sorted_data     : Mutex<Vec<Vertex>>,
+    culled_data     : Mutex<Vec<Vertex>>,
+    cull_matrices   : Mutex<Option<(Mat4, Mat4)>>,
+    sort_mode       : Mutex<LayerSortMode>,
     dirty           : AtomicBool,
+    unsorted        : AtomicBool,
     generation      : AtomicUsize,
     layer_id        : usize,
+    retained        : Option<Mutex<RetainedData>>,
+}
+
+/// Contents of a retained layer.
+#[derive(Debug, Default)]
+pub(crate) struct RetainedData {
+    vertices        : Vec<Vertex>,
+    /// Sprite of each quad, if the quad was added with a handle.
+    sprites         : Vec<Option<RetainedSprite>>,
+    /// Quads of removed sprites, available for reuse.
+    free            : Vec<usize>,
+    /// Range of vertices changed since the layer was last rendered.
+    dirty           : Option<(usize, usize)>,
+    serial          : u32,
+}
+
+impl RetainedData {
+    /// Adds given vertex range to the dirty range.
+    fn mark_dirty(self: &mut Self, start: usize, end: usize) {
+        self.dirty = Some(match self.dirty {
+            Some((dirty_start, dirty_end)) => (dirty_start.min(start), dirty_end.max(end)),
+            None => (start, end),
+        });
+    }
+}
+
+/// A sprite on a retained layer.
+#[derive(Debug)]
+struct RetainedSprite {
+    serial          : u32,
+    sprite          : Sprite,
+    frame_id        : u32,
+    position        : Point2,
+    color           : Color,
+    rotation        : f32,
+    scale           : Point2,
+    visible         : bool,
+}
+
+/// A handle to a sprite on a retained layer, returned by [`Layer::add_sprite()`](struct.Layer.html#method.add_sprite).
+///
+/// Handles become invalid when the sprite is removed or the layer is cleared. Using an invalid handle panics.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpriteHandle {
+    layer_id    : usize,
+    index       : usize,
+    serial      : u32,
 }
 
 impl Debug for LayerContents {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("LayerContents")
             .field("num_sprites", &(self.vertex_data.len() / 4))
+            .field("sort_mode", &self.sort_mode)
             .field("dirty", &self.dirty)
             .field("generation", &self.generation)
             .field("layer_id", &self.layer_id)
+            .field("retained", &self.retained.is_some())
             .finish()
     }
 }
@@ -66,6 +178,11 @@ impl Layer {
         Self::create(dimensions, None)
     }
 
+    /// Creates a new retained layer with given dimensions. See [`add_sprite()`](#method.add_sprite).
+    pub fn retained<T>(dimensions: T) -> Self where Point2<f32>: From<T> {
+        Self::create_with_capacity(dimensions, None, context::INITIAL_CAPACITY, true)
+    }
+
     /// Creates a new layer with given dimensions and fragment program.
     pub fn with_program<T>(dimensions: T, program: Program) -> Self where Point2<f32>: From<T> {
         Self::create(dimensions, Some(program))
@@ -137,23 +254,157 @@ impl Layer {
         self.blend.lock().unwrap()
     }
 
+    /// Sets the order in which the layer's contents are drawn. Defaults to `LayerSortMode::Insertion`.
+    ///
+    /// When multiple threads draw onto the same layer, insertion order depends on thread timing. Sorting by z or by
+    /// vertical position makes the draw order independent of it. Contents with equal sort keys are ordered by
+    /// position and texture. Draw calls made of multiple quads, like text, nine-slice sprites, primitives and meshes,
+    /// are sorted as a unit by the position of their first quad. Sorting happens when the layer is rendered after its
+    /// contents changed and affects all clones of the layer.
+    pub fn set_sort_mode(self: &Self, sort_mode: LayerSortMode) -> &Self {
+        *self.contents.sort_mode.lock().unwrap() = sort_mode;
+        self.set_dirty(true);
+        self
+    }
+
+    /// Returns the order in which the layer's contents are drawn.
+    pub fn sort_mode(self: &Self) -> LayerSortMode {
+        *self.contents.sort_mode.lock().unwrap()
+    }
+
+    /// Enables or disables culling. Defaults to disabled.
+    ///
+    /// When enabled, sprites and primitives that lie entirely outside of the area mapped to the drawing target by the
+    /// view and model matrices are discarded on the CPU before the layer is rendered. This benefits layers that contain
+    /// many objects of which only a few are visible, at the cost of re-uploading the visible ones whenever the layer
+    /// is rendered with different matrices or after its contents changed. Culling does not account for custom vertex
+    /// transformations done by shaders.
+    pub fn set_culling(self: &Self, enabled: bool) -> &Self {
+        self.culling.store(enabled, Ordering::Relaxed);
+        self
+    }
+
+    /// Returns whether culling is enabled.
+    pub fn culling(self: &Self) -> bool {
+        self.culling.load(Ordering::Relaxed)
+    }
+
     /// Removes all previously added objects from the layer. Typically invoked after the layer has
     /// been rendered.
     pub fn clear(self: &Self) -> &Self {
         self.set_dirty(true);
         self.set_generation(0);
-        self.contents.vertex_data.clear();
+        if let Some(ref retained) = self.contents.retained {
+            let mut retained = retained.lock().unwrap();
+            retained.vertices.clear();
+            retained.sprites.clear();
+            retained.free.clear();
+        } else {
+            self.contents.vertex_data.clear();
+        }
         self
     }
 
     /// Returns the number of sprites the layer can hold without having to perform a blocking reallocation.
     pub fn capacity(self: &Self) -> usize {
-        self.contents.vertex_data.capacity() / 4
+        match self.contents.retained {
+            Some(ref retained) => retained.lock().unwrap().vertices.capacity() / 4,
+            None => self.contents.vertex_data.capacity() / 4,
+        }
     }
 
-    /// Returns the number of sprites currently stored the layer.
+    /// Returns the number of sprites currently stored the layer. For retained layers this includes the storage of
+    /// removed sprites that has not been reused yet.
     pub fn len(self: &Self) -> usize {
-        self.contents.vertex_data.len() / 4
+        match self.contents.retained {
+            Some(ref retained) => retained.lock().unwrap().vertices.len() / 4,
+            None => self.contents.vertex_data.len() / 4,
+        }
+    }
+
+    /// Returns whether the layer was created with [`Layer::retained()`](#method.retained).
+    pub fn is_retained(self: &Self) -> bool {
+        self.contents.retained.is_some()
+    }
+
+    /// Adds a sprite to a retained layer and returns a handle to update or remove it. Panics if the layer is not
+    /// retained.
+    pub fn add_sprite<T>(self: &Self, sprite: &Sprite, frame_id: u32, position: T, color: Color) -> SpriteHandle where Point2: From<T> {
+        let mut retained = self.contents.retained.as_ref().expect("Sprites can only be added to retained layers.").lock().unwrap();
+        let entry = RetainedSprite {
+            serial      : retained.serial,
+            sprite      : sprite.clone(),
+            frame_id    : frame_id,
+            position    : Point2::from(position),
+            color       : color,
+            rotation    : 0.0,
+            scale       : (1.0, 1.0),
+            visible     : true,
+        };
+        retained.serial = retained.serial.wrapping_add(1);
+        let index = match retained.free.pop() {
+            Some(index) => index,
+            None => {
+                retained.vertices.extend_from_slice(&[ Vertex::default(); 4 ]);
+                retained.sprites.push(None);
+                retained.sprites.len() - 1
+            }
+        };
+        let handle = SpriteHandle {
+            layer_id    : self.contents.layer_id,
+            index       : index,
+            serial      : entry.serial,
+        };
+        retained.sprites[index] = Some(entry);
+        self.write_sprite(&mut retained, index);
+        handle
+    }
+
+    /// Moves the sprite of given handle to given position.
+    pub fn set_sprite_position<T>(self: &Self, handle: SpriteHandle, position: T) -> &Self where Point2: From<T> {
+        let position = Point2::from(position);
+        self.update_sprite(handle, |sprite| sprite.position = position)
+    }
+
+    /// Changes the frame of the sprite of given handle.
+    pub fn set_sprite_frame(self: &Self, handle: SpriteHandle, frame_id: u32) -> &Self {
+        self.update_sprite(handle, |sprite| sprite.frame_id = frame_id)
+    }
+
+    /// Changes the color of the sprite of given handle.
+    pub fn set_sprite_color(self: &Self, handle: SpriteHandle, color: Color) -> &Self {
+        self.update_sprite(handle, |sprite| sprite.color = color)
+    }
+
+    /// Changes rotation and scaling of the sprite of given handle.
+    pub fn set_sprite_transform<T>(self: &Self, handle: SpriteHandle, rotation: f32, scale: T) -> &Self where Point2: From<T> {
+        let scale = Point2::from(scale);
+        self.update_sprite(handle, |sprite| { sprite.rotation = rotation; sprite.scale = scale; })
+    }
+
+    /// Shows or hides the sprite of given handle.
+    pub fn set_sprite_visible(self: &Self, handle: SpriteHandle, visible: bool) -> &Self {
+        self.update_sprite(handle, |sprite| sprite.visible = visible)
+    }
+
+    /// Removes the sprite of given handle from the layer. The handle becomes invalid.
+    pub fn remove_sprite(self: &Self, handle: SpriteHandle) -> &Self {
+        let mut retained = self.retained_data(handle);
+        retained.sprites[handle.index] = None;
+        retained.free.push(handle.index);
+        self.write_sprite(&mut retained, handle.index);
+        self
+    }
+
+    /// Returns whether given handle refers to a sprite on this layer.
+    pub fn contains_sprite(self: &Self, handle: SpriteHandle) -> bool {
+        match self.contents.retained {
+            Some(ref retained) if handle.layer_id == self.contents.layer_id => {
+                let retained = retained.lock().unwrap();
+                retained.sprites.get(handle.index).map_or(false, |sprite| sprite.as_ref().map_or(false, |sprite| sprite.serial == handle.serial))
+            }
+            _ => false,
+        }
     }
 
     /// Returns the layer wrapped in an std::Arc.
@@ -161,13 +412,148 @@ impl Layer {
         Arc::new(self)
     }
 
-    /// Draws a rectangle on given layer.
-    pub(crate) fn add_rect(self: &Self, generation: Option<usize>, bucket_id: u8, texture_id: u32, components: u8, uv: Rect, pos: Point2, anchor: Point2<f32>, dim: Point2, color: Color, rotation: f32, scale: Point2) {
+    /// Draws a filled rectangle given as `((left, top), (width, height))`.
+    pub fn fill_rect<T>(self: &Self, rect: T, color: Color, z: f32) -> &Self where Rect: From<T> {
+        let ((x, y), (width, height)) = Rect::from(rect);
+        let center = (x + width / 2.0, y + height / 2.0);
+        self.add_quad(None, Self::untextured_quad(center, Self::rect_offsets(center, ((x, y), (width, height))), color, z));
+        self
+    }
 
-        self.set_dirty(true);
-        if generation.is_some() && !self.set_generation(generation.unwrap()) {
-            panic!("Layer contains garbage data. Note: Layers need to be cleared after performing a Context::prune().");
+    /// Draws the outline of a rectangle given as `((left, top), (width, height))`. The outline extends `thickness`
+    /// pixels inwards from the rectangle's edges.
+    pub fn stroke_rect<T>(self: &Self, rect: T, thickness: f32, color: Color, z: f32) -> &Self where Rect: From<T> {
+        let ((x, y), (width, height)) = Rect::from(rect);
+        let thickness = thickness.min(width / 2.0).min(height / 2.0);
+        let center = (x + width / 2.0, y + height / 2.0);
+        let edges: [Rect; 4] = [
+            ((x, y), (width, thickness)),
+            ((x, y + height - thickness), (width, thickness)),
+            ((x, y + thickness), (thickness, height - thickness * 2.0)),
+            ((x + width - thickness, y + thickness), (thickness, height - thickness * 2.0)),
+        ];
+        let quads: Vec<[Vertex; 4]> = edges.iter().map(|&edge| Self::untextured_quad(center, Self::rect_offsets(center, edge), color, z)).collect();
+        self.add_quads(None, &quads);
+        self
+    }
+
+    /// Draws a line of given thickness.
+    pub fn line<T, U>(self: &Self, from: T, to: U, thickness: f32, color: Color, z: f32) -> &Self where Point2: From<T>, Point2: From<U> {
+        let (from, to) = (Point2::from(from), Point2::from(to));
+        let center = ((from.0 + to.0) / 2.0, (from.1 + to.1) / 2.0);
+        let (dx, dy) = ((to.0 - from.0) / 2.0, (to.1 - from.1) / 2.0);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length > 0.0 {
+            let (nx, ny) = (-dy / length * thickness / 2.0, dx / length * thickness / 2.0);
+            self.add_quad(None, Self::untextured_quad(center, [ (-dx - nx, -dy - ny), (dx - nx, dy - ny), (-dx + nx, -dy + ny), (dx + nx, dy + ny) ], color, z));
         }
+        self
+    }
+
+    /// Draws a filled circle.
+    pub fn fill_circle<T>(self: &Self, center: T, radius: f32, color: Color, z: f32) -> &Self where Point2: From<T> {
+        self.fill_arc(center, radius, 0.0, 2.0 * f32::consts::PI, color, z)
+    }
+
+    /// Draws the outline of a circle. The outline is centered on the circle's radius.
+    pub fn stroke_circle<T>(self: &Self, center: T, radius: f32, thickness: f32, color: Color, z: f32) -> &Self where Point2: From<T> {
+        self.stroke_arc(center, radius, 0.0, 2.0 * f32::consts::PI, thickness, color, z)
+    }
+
+    /// Draws a filled circle segment from `start` to `end` angle, given in radians clockwise from the positive x axis.
+    pub fn fill_arc<T>(self: &Self, center: T, radius: f32, start: f32, end: f32, color: Color, z: f32) -> &Self where Point2: From<T> {
+        let mut fan = vec![ (0.0, 0.0) ];
+        fan.extend(Self::arc_points(radius, start, end).into_iter().map(|point| (point.0 * radius, point.1 * radius)));
+        self.add_quads(None, &Self::untextured_fan(Point2::from(center), &fan, color, z));
+        self
+    }
+
+    /// Draws an arc from `start` to `end` angle, given in radians clockwise from the positive x axis. The arc is
+    /// centered on the radius.
+    pub fn stroke_arc<T>(self: &Self, center: T, radius: f32, start: f32, end: f32, thickness: f32, color: Color, z: f32) -> &Self where Point2: From<T> {
+        let center = Point2::from(center);
+        let (inner, outer) = ((radius - thickness / 2.0).max(0.0), radius + thickness / 2.0);
+        let points = Self::arc_points(outer, start, end);
+        let quads: Vec<[Vertex; 4]> = points.windows(2).map(|segment| {
+            let (a, b) = (segment[0], segment[1]);
+            Self::untextured_quad(center, [ (a.0 * inner, a.1 * inner), (b.0 * inner, b.1 * inner), (a.0 * outer, a.1 * outer), (b.0 * outer, b.1 * outer) ], color, z)
+        }).collect();
+        self.add_quads(None, &quads);
+        self
+    }
+
+    /// Draws a filled convex polygon. Concave polygons are not drawn correctly.
+    pub fn fill_polygon(self: &Self, points: &[Point2], color: Color, z: f32) -> &Self {
+        if points.len() >= 3 {
+            let center = Self::centroid(points);
+            let fan: Vec<Point2> = points.iter().map(|point| (point.0 - center.0, point.1 - center.1)).collect();
+            self.add_quads(None, &Self::untextured_fan(center, &fan, color, z));
+        }
+        self
+    }
+
+    /// Draws the closed outline of a convex polygon. The outline is centered on the polygon's edges and uses mitered joins.
+    pub fn stroke_polygon(self: &Self, points: &[Point2], thickness: f32, color: Color, z: f32) -> &Self {
+        let num_points = points.len();
+        if num_points < 2 {
+            return self;
+        }
+        let center = Self::centroid(points);
+        let normal = |a: Point2, b: Point2| {
+            let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+            let length = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+            (-dy / length, dx / length)
+        };
+        // inner and outer vertex for each point, relative to the center
+        let ring: Vec<(Point2, Point2)> = (0..num_points).map(|index| {
+            let point = points[index];
+            let previous = normal(points[(index + num_points - 1) % num_points], point);
+            let next = normal(point, points[(index + 1) % num_points]);
+            let (mx, my) = (previous.0 + next.0, previous.1 + next.1);
+            let length = (mx * mx + my * my).sqrt().max(f32::EPSILON);
+            let (mx, my) = (mx / length, my / length);
+            // limit miter length for very sharp corners
+            let miter = thickness / 2.0 / (mx * next.0 + my * next.1).max(0.25);
+            let point = (point.0 - center.0, point.1 - center.1);
+            ((point.0 - mx * miter, point.1 - my * miter), (point.0 + mx * miter, point.1 + my * miter))
+        }).collect();
+        let quads: Vec<[Vertex; 4]> = (0..num_points).map(|index| {
+            let (a, b) = (ring[index], ring[(index + 1) % num_points]);
+            Self::untextured_quad(center, [ a.0, b.0, a.1, b.1 ], color, z)
+        }).collect();
+        self.add_quads(None, &quads);
+        self
+    }
+
+    /// Draws an untextured triangle mesh at given position. Each consecutive three `indices` reference the vertices of
+    /// a triangle. Panics if the number of indices is not a multiple of three or an index is out of range.
+    pub fn draw_mesh<T>(self: &Self, position: T, vertices: &[MeshVertex], indices: &[u32], z: f32) -> &Self where Point2: From<T> {
+        let position = Point2::from(position);
+        self.add_mesh(None, vertices, indices, |vertex| {
+            let mut result = Self::untextured_vertex(position, vertex.position, vertex.color, z);
+            result.texture_uv = [ vertex.uv.0, vertex.uv.1 ];
+            result
+        });
+        self
+    }
+
+    /// Draws a triangle mesh at given position, textured with the texture given to
+    /// [`Renderer::draw_layer_with_texture()`](struct.Renderer.html#method.draw_layer_with_texture). Meshes
+    /// rendered without a texture are transparent. See [`draw_mesh()`](#method.draw_mesh) for a description of the
+    /// indices.
+    pub fn draw_textured_mesh<T>(self: &Self, position: T, vertices: &[MeshVertex], indices: &[u32], z: f32) -> &Self where Point2: From<T> {
+        let position = Point2::from(position);
+        self.add_mesh(None, vertices, indices, |vertex| {
+            let mut result = Self::untextured_vertex(position, vertex.position, vertex.color, z);
+            result.bucket_id = LAYER_TEXTURE_BUCKET;
+            result.texture_uv = [ vertex.uv.0, vertex.uv.1 ];
+            result
+        });
+        self
+    }
+
+    /// Returns the vertices of a rectangle.
+    pub(crate) fn rect_vertices(bucket_id: u8, texture_id: u32, components: u8, uv: Rect, pos: Point2, anchor: Point2<f32>, dim: Point2, color: Color, rotation: f32, scale: Point2) -> [Vertex; 4] {
 
         // corner positions relative to x/y
 
@@ -179,53 +565,240 @@ impl Layer {
         let bucket_id = bucket_id as u32;
         let components = components as u32;
 
+        [
+            Vertex {
+                position    : [pos.0, pos.1],
+                offset      : [offset_x0, offset_y0],
+                rotation    : rotation,
+                color       : color.into(),
+                bucket_id   : bucket_id,
+                texture_id  : texture_id,
+                texture_uv  : uv.top_left().as_array(),
+                components  : components,
+                z           : 0.0,
+                draw_quads  : 0,
+            },
+            Vertex {
+                position    : [pos.0, pos.1],
+                offset      : [offset_x1, offset_y0],
+                rotation    : rotation,
+                color       : color.into(),
+                bucket_id   : bucket_id,
+                texture_id  : texture_id,
+                texture_uv  : uv.top_right().as_array(),
+                components  : components,
+                z           : 0.0,
+                draw_quads  : 0,
+            },
+            Vertex {
+                position    : [pos.0, pos.1],
+                offset      : [offset_x0, offset_y1],
+                rotation    : rotation,
+                color       : color.into(),
+                bucket_id   : bucket_id,
+                texture_id  : texture_id,
+                texture_uv  : uv.bottom_left().as_array(),
+                components  : components,
+                z           : 0.0,
+                draw_quads  : 0,
+            },
+            Vertex {
+                position    : [pos.0, pos.1],
+                offset      : [offset_x1, offset_y1],
+                rotation    : rotation,
+                color       : color.into(),
+                bucket_id   : bucket_id,
+                texture_id  : texture_id,
+                texture_uv  : uv.bottom_right().as_array(),
+                components  : components,
+                z           : 0.0,
+                draw_quads  : 0,
+            },
+        ]
+    }
+
+    /// Draws a quad given as top left, top right, bottom left and bottom right vertex on given layer.
+    pub(crate) fn add_quad(self: &Self, generation: Option<usize>, vertices: [Vertex; 4]) {
+        self.add_quads(generation, &[ vertices ]);
+    }
+
+    /// Draws given quads on given layer. The quads are stored contiguously and sorted as a single draw call.
+    pub(crate) fn add_quads(self: &Self, generation: Option<usize>, quads: &[[Vertex; 4]]) {
+
+        if quads.len() == 0 {
+            return;
+        }
+
+        self.check_generation(generation);
+
+        let vertex = |index: usize| {
+            let mut vertex = quads[index / 4][index % 4];
+            vertex.draw_quads = if index == 0 { quads.len() as u32 } else { 0 };
+            vertex
+        };
+
+        // retained layers append to their locked storage
+
+        if let Some(ref retained) = self.contents.retained {
+            let mut retained = retained.lock().unwrap();
+            let start = retained.vertices.len();
+            retained.vertices.extend((0..quads.len() * 4).map(vertex));
+            retained.sprites.extend(quads.iter().map(|_| None));
+            retained.mark_dirty(start, start + quads.len() * 4);
+            self.contents.unsorted.store(true, Ordering::Relaxed);
+            return;
+        }
+
+        self.set_dirty(true);
+
         // get vertex_data slice and draw into it
 
-        let map = self.contents.vertex_data.map(4);
+        let map = self.contents.vertex_data.map(quads.len() * 4);
 
-        map.set(0, Vertex {
-            position    : [pos.0, pos.1],
-            offset      : [offset_x0, offset_y0],
-            rotation    : rotation,
-            color       : color.into(),
-            bucket_id   : bucket_id,
-            texture_id  : texture_id,
-            texture_uv  : uv.top_left().as_array(),
-            components  : components,
-        });
+        for index in 0..quads.len() * 4 {
+            map.set(index, vertex(index));
+        }
+    }
 
-        map.set(1, Vertex {
-            position    : [pos.0, pos.1],
-            offset      : [offset_x1, offset_y0],
-            rotation    : rotation,
-            color       : color.into(),
-            bucket_id   : bucket_id,
-            texture_id  : texture_id,
-            texture_uv  : uv.top_right().as_array(),
-            components  : components,
-        });
+    /// Panics if the layer contains data of a different content generation.
+    fn check_generation(self: &Self, generation: Option<usize>) {
+        if generation.is_some() && !self.set_generation(generation.unwrap()) {
+            panic!("Layer contains garbage data. Note: Layers need to be cleared after performing a Context::prune().");
+        }
+    }
 
-        map.set(2, Vertex {
-            position    : [pos.0, pos.1],
-            offset      : [offset_x0, offset_y1],
-            rotation    : rotation,
-            color       : color.into(),
-            bucket_id   : bucket_id,
-            texture_id  : texture_id,
-            texture_uv  : uv.bottom_left().as_array(),
-            components  : components,
-        });
+    /// Returns the locked contents of a retained layer after verifying that given handle refers to one of its sprites.
+    fn retained_data(self: &Self, handle: SpriteHandle) -> MutexGuard<RetainedData> {
+        let retained = self.contents.retained.as_ref().expect("Sprite handles can only be used with retained layers.").lock().unwrap();
+        let valid = handle.layer_id == self.contents.layer_id && retained.sprites.get(handle.index).map_or(false, |sprite| sprite.as_ref().map_or(false, |sprite| sprite.serial == handle.serial));
+        assert!(valid, "Invalid sprite handle. The sprite was removed, the layer was cleared or the handle belongs to a different layer.");
+        retained
+    }
+
+    /// Applies given change to the sprite of given handle and updates its vertices.
+    fn update_sprite<F>(self: &Self, handle: SpriteHandle, change: F) -> &Self where F: FnOnce(&mut RetainedSprite) {
+        let mut retained = self.retained_data(handle);
+        change(retained.sprites[handle.index].as_mut().unwrap());
+        self.write_sprite(&mut retained, handle.index);
+        self
+    }
+
+    /// Writes the vertices of the sprite at given index of a retained layer. Removed or hidden sprites are written as
+    /// degenerate quads.
+    fn write_sprite(self: &Self, retained: &mut RetainedData, index: usize) {
+        let mut vertices = match retained.sprites[index] {
+            Some(ref sprite) if sprite.visible => {
+                self.check_generation(Some(sprite.sprite.generation()));
+                sprite.sprite.frame_vertices(sprite.frame_id, sprite.position, sprite.color, sprite.rotation, sprite.scale)
+            }
+            _ => [ Vertex::default(); 4 ],
+        };
+        vertices[0].draw_quads = 1;
+        retained.vertices[index * 4..index * 4 + 4].copy_from_slice(&vertices);
+        retained.mark_dirty(index * 4, index * 4 + 4);
+        self.contents.unsorted.store(true, Ordering::Relaxed);
+    }
+
+    /// Draws a triangle mesh on given layer, converting mesh vertices to layer vertices with given function.
+    /// Consecutive triangles sharing an edge are packed into a single quad, others are drawn as degenerate quads.
+    pub(crate) fn add_mesh<F>(self: &Self, generation: Option<usize>, vertices: &[MeshVertex], indices: &[u32], convert: F) where F: Fn(&MeshVertex) -> Vertex {
+
+        assert!(indices.len() % 3 == 0, "Number of mesh indices must be a multiple of three.");
+
+        let vertex = |index: u32| {
+            let mesh_vertex = vertices.get(index as usize).unwrap_or_else(|| panic!("Mesh index {} is out of range.", index));
+            convert(mesh_vertex)
+        };
+
+        let triangles: Vec<&[u32]> = indices.chunks(3).collect();
+        let mut quads = Vec::with_capacity(triangles.len());
+        let mut index = 0;
+
+        while index < triangles.len() {
+            let (a, b, c) = (triangles[index][0], triangles[index][1], triangles[index][2]);
+            // a quad [ v0, v1, v2, v3 ] is drawn as triangles (v0, v1, v2) and (v1, v3, v2)
+            let paired = triangles.get(index + 1).and_then(|next| {
+                [ (a, b, c), (b, c, a), (c, a, b) ].iter().filter_map(|&(v0, v1, v2)| {
+                    if next.contains(&v1) && next.contains(&v2) {
+                        next.iter().find(|&&v3| v3 != v1 && v3 != v2).map(|&v3| [ v0, v1, v2, v3 ])
+                    } else {
+                        None
+                    }
+                }).next()
+            });
+            let quad = match paired {
+                Some(quad) => { index += 2; quad }
+                None => { index += 1; [ a, b, c, c ] }
+            };
+            quads.push([ vertex(quad[0]), vertex(quad[1]), vertex(quad[2]), vertex(quad[3]) ]);
+        }
+
+        self.add_quads(generation, &quads);
+    }
+
+    /// Returns an untextured quad given as top left, top right, bottom left and bottom right offset from position.
+    fn untextured_quad(position: Point2, offsets: [Point2; 4], color: Color, z: f32) -> [Vertex; 4] {
+        let vertex = |offset: Point2| Self::untextured_vertex(position, offset, color, z);
+        [ vertex(offsets[0]), vertex(offsets[1]), vertex(offsets[2]), vertex(offsets[3]) ]
+    }
 
-        map.set(3, Vertex {
-            position    : [pos.0, pos.1],
-            offset      : [offset_x1, offset_y1],
-            rotation    : rotation,
+    /// Returns an untextured triangle fan around `fan[0]`, given as offsets from position. Two triangles are packed
+    /// into each quad, a remaining triangle is drawn as a degenerate quad.
+    fn untextured_fan(position: Point2, fan: &[Point2], color: Color, z: f32) -> Vec<[Vertex; 4]> {
+        let vertex = |offset: Point2| Self::untextured_vertex(position, offset, color, z);
+        let hub = fan[0];
+        let mut quads = Vec::new();
+        let mut index = 1;
+        while index + 1 < fan.len() {
+            // triangles (a, hub, b) and (hub, c, b)
+            let (a, b) = (fan[index], fan[index + 1]);
+            let c = if index + 2 < fan.len() { fan[index + 2] } else { b };
+            quads.push([ vertex(a), vertex(hub), vertex(b), vertex(c) ]);
+            index += 2;
+        }
+        quads
+    }
+
+    /// Creates a vertex for untextured geometry.
+    fn untextured_vertex(position: Point2, offset: Point2, color: Color, z: f32) -> Vertex {
+        Vertex {
+            position    : [position.0, position.1],
+            offset      : [offset.0, offset.1],
+            rotation    : 0.0,
             color       : color.into(),
-            bucket_id   : bucket_id,
-            texture_id  : texture_id,
-            texture_uv  : uv.bottom_right().as_array(),
-            components  : components,
-        });
+            bucket_id   : UNTEXTURED_BUCKET,
+            texture_id  : 0,
+            texture_uv  : [0.0, 0.0],
+            components  : 1,
+            z           : z,
+            draw_quads  : 0,
+        }
+    }
+
+    /// Returns the corners of given rectangle as offsets from given position.
+    fn rect_offsets(position: Point2, rect: Rect) -> [Point2; 4] {
+        let ((x, y), (width, height)) = rect;
+        let (left, top) = (x - position.0, y - position.1);
+        let (right, bottom) = (left + width, top + height);
+        [ (left, top), (right, top), (left, bottom), (right, bottom) ]
+    }
+
+    /// Returns points on the unit circle from start to end angle, including both ends. The number of points is chosen
+    /// to approximate a circle of given radius.
+    fn arc_points(radius: f32, start: f32, end: f32) -> Vec<Point2> {
+        let angle = end - start;
+        // segments of about four pixels, but at least one per 1/32 of a circle
+        let segments = ((radius * angle.abs() / 4.0).ceil() as u32).max((angle.abs() / (f32::consts::PI / 16.0)).ceil() as u32).max(1).min(256);
+        (0..segments + 1).map(|segment| {
+            let angle = start + angle * segment as f32 / segments as f32;
+            (angle.cos(), angle.sin())
+        }).collect()
+    }
+
+    /// Returns the average of given points.
+    fn centroid(points: &[Point2]) -> Point2 {
+        let sum = points.iter().fold((0.0, 0.0), |sum, point| (sum.0 + point.0, sum.1 + point.1));
+        (sum.0 / points.len() as f32, sum.1 / points.len() as f32)
     }
 
     /// Returns a reference to the program used by this layer.
@@ -233,9 +806,87 @@ impl Layer {
         self.program.as_ref()
     }
 
-    /// Returns the readguard protected vertex data.
-    pub(crate) fn vertices(self: &Self) -> avec::AVecReadGuard<Vertex> {
-        self.contents.vertex_data.get()
+    /// Returns the vertex data in drawing order, sorting it first if required and discarding invisible quads if
+    /// culling is enabled.
+    pub(crate) fn vertices(self: &Self) -> LayerVertices {
+        let vertices = self.ordered_vertices();
+        if !self.culling() {
+            return vertices;
+        }
+        let model_matrix = *self.model_matrix().deref().deref();
+        let view_matrix = *self.view_matrix().deref().deref();
+        let mut culled = self.contents.culled_data.lock().unwrap();
+        Self::cull_vertices(&mut culled, &vertices, &model_matrix, &view_matrix);
+        LayerVertices::Culled(culled)
+    }
+
+    /// Writes those of given vertices to the target that belong to quads visible through given matrices.
+    fn cull_vertices(target: &mut Vec<Vertex>, vertices: &[Vertex], model_matrix: &Mat4, view_matrix: &Mat4) {
+        // same transformation as in the vertex shader, without the view matrix
+        let transform = |vertex: &Vertex| {
+            let (sin, cos) = vertex.rotation.sin_cos();
+            let x = vertex.offset[0] * cos - vertex.offset[1] * sin;
+            let y = vertex.offset[0] * sin + vertex.offset[1] * cos;
+            let m = model_matrix;
+            (vertex.position[0] + m[0][0] * x + m[1][0] * y + m[3][0], vertex.position[1] + m[0][1] * x + m[1][1] * y + m[3][1])
+        };
+        target.clear();
+        for quad in vertices.chunks(4) {
+            let corners = [ transform(&quad[0]), transform(&quad[1]), transform(&quad[2]), transform(&quad[3]) ];
+            if points_visible(&corners, view_matrix) {
+                target.extend_from_slice(quad);
+            }
+        }
+    }
+
+    /// Returns the vertex data in drawing order, sorting it first if required.
+    fn ordered_vertices(self: &Self) -> LayerVertices {
+        let sort_mode = self.sort_mode();
+        if sort_mode == LayerSortMode::Insertion {
+            return match self.contents.retained {
+                Some(ref retained) => LayerVertices::Retained(retained.lock().unwrap()),
+                None => LayerVertices::Unsorted(self.contents.vertex_data.get()),
+            };
+        }
+        let mut sorted = self.contents.sorted_data.lock().unwrap();
+        if self.contents.unsorted.swap(false, Ordering::Relaxed) {
+            match self.contents.retained {
+                Some(ref retained) => Self::sort_vertices(&mut sorted, &retained.lock().unwrap().vertices, sort_mode),
+                None => Self::sort_vertices(&mut sorted, &self.contents.vertex_data.get(), sort_mode),
+            }
+        }
+        LayerVertices::Sorted(sorted)
+    }
+
+    /// Writes given vertices to the target sorted by given sort mode. The quads of a draw call are kept together and
+    /// sorted by the draw call's first vertex.
+    fn sort_vertices(target: &mut Vec<Vertex>, vertices: &[Vertex], sort_mode: LayerSortMode) {
+        let mut draw_calls = Vec::new();
+        let mut start = 0;
+        while start < vertices.len() {
+            // vertices not written by add_quads(), e.g. of removed retained sprites, count as single quads
+            let end = (start + vertices[start].draw_quads.max(1) as usize * 4).min(vertices.len());
+            draw_calls.push(&vertices[start..end]);
+            start = end;
+        }
+        draw_calls.sort_by(|a, b| Self::compare_draw_calls(sort_mode, &a[0], &b[0]));
+        target.clear();
+        for draw_call in draw_calls {
+            target.extend_from_slice(draw_call);
+        }
+    }
+
+    /// Compares two draw calls by their first vertex for given sort mode.
+    fn compare_draw_calls(sort_mode: LayerSortMode, a: &Vertex, b: &Vertex) -> cmp::Ordering {
+        let keys = |vertex: &Vertex| match sort_mode {
+            LayerSortMode::Y => [ vertex.position[1], vertex.z, vertex.position[0], vertex.offset[1], vertex.offset[0] ],
+            _ => [ vertex.z, vertex.position[1], vertex.position[0], vertex.offset[1], vertex.offset[0] ],
+        };
+        let (keys_a, keys_b) = (keys(a), keys(b));
+        keys_a.iter().zip(keys_b.iter())
+            .map(|(a, b)| a.partial_cmp(b).unwrap_or(cmp::Ordering::Equal))
+            .find(|&ordering| ordering != cmp::Ordering::Equal)
+            .unwrap_or_else(|| (a.bucket_id, a.texture_id).cmp(&(b.bucket_id, b.texture_id)))
     }
 
     /// Returns the layer id.
@@ -243,24 +894,72 @@ impl Layer {
         self.contents.layer_id
     }
 
-    /// Flags the layer as no longer dirty and returns whether it was dirty.
-    pub(crate) fn undirty(self: &Self) -> bool {
-        self.contents.dirty.swap(false, Ordering::Relaxed)
+    /// Flags the layer as no longer dirty and returns the range of vertices that changed since the last call.
+    pub(crate) fn undirty(self: &Self) -> Option<(usize, usize)> {
+        let dirty = self.contents.dirty.swap(false, Ordering::Relaxed);
+        let range = match self.contents.retained {
+            Some(ref retained) => {
+                let mut retained = retained.lock().unwrap();
+                let range = retained.dirty.take();
+                // sorting may move any vertex
+                if dirty || (range.is_some() && self.sort_mode() != LayerSortMode::Insertion) {
+                    Some((0, retained.vertices.len()))
+                } else {
+                    range
+                }
+            }
+            None if dirty => Some((0, self.contents.vertex_data.len())),
+            None => None,
+        };
+        // culled vertices depend on the matrices of the rendered layer clone, any change may add or remove quads
+        let matrices = if self.culling() {
+            Some((*self.model_matrix().deref().deref(), *self.view_matrix().deref().deref()))
+        } else {
+            None
+        };
+        let mut cull_matrices = self.contents.cull_matrices.lock().unwrap();
+        if *cull_matrices != matrices || (matrices.is_some() && range.is_some()) {
+            *cull_matrices = matrices;
+            Some((0, self.len() * 4))
+        } else {
+            range
+        }
+    }
+
+    /// Creates a new layer with room for given number of sprites before it has to reallocate.
+    pub(crate) fn with_capacity<T>(dimensions: T, capacity: usize) -> Self where Point2<f32>: From<T> {
+        Self::create_with_capacity(dimensions, None, capacity, false)
     }
 
     /// Creates a new layer
     fn create<T>(dimensions: T, program: Option<Program>) -> Self where Point2<f32>: From<T> {
+        Self::create_with_capacity(dimensions, program, context::INITIAL_CAPACITY, false)
+    }
+
+    /// Creates a new immediate or retained layer with given capacity.
+    fn create_with_capacity<T>(dimensions: T, program: Option<Program>, capacity: usize, retained: bool) -> Self where Point2<f32>: From<T> {
         let dimensions = Point2::from(dimensions);
         Layer {
             view_matrix     : Mutex::new(Mat4::viewport(dimensions.0, dimensions.1).into()),
             model_matrix    : Mutex::new(Mat4::identity().into()),
             blend           : Mutex::new(blendmodes::ALPHA),
             color           : Mutex::new(Color::WHITE),
+            culling         : AtomicBool::new(false),
             contents        : Arc::new(LayerContents {
-                vertex_data     : avec::AVec::new(context::INITIAL_CAPACITY * 4),
+                vertex_data     : avec::AVec::new(capacity * 4),
+                sorted_data     : Mutex::new(Vec::new()),
+                culled_data     : Mutex::new(Vec::new()),
+                cull_matrices   : Mutex::new(None),
+                sort_mode       : Mutex::new(LayerSortMode::Insertion),
                 dirty           : AtomicBool::new(true),
+                unsorted        : AtomicBool::new(true),
                 generation      : AtomicUsize::new(0),
                 layer_id        : 1 + LAYER_COUNTER.fetch_add(1, Ordering::Relaxed),
+                retained        : if retained {
+                    Some(Mutex::new(RetainedData { vertices: Vec::with_capacity(capacity * 4), ..RetainedData::default() }))
+                } else {
+                    None
+                },
             }),
             program         : program,
         }
@@ -273,6 +972,7 @@ impl Layer {
             model_matrix    : Mutex::new(self.model_matrix().clone().into()),
             blend           : Mutex::new(self.blendmode().clone()),
             color           : Mutex::new(self.color().clone()),
+            culling         : AtomicBool::new(self.culling()),
             contents        : self.contents.clone(),
             program         : program,
         }
@@ -288,6 +988,77 @@ impl Layer {
     /// Sets or unsets the layers dirty state
     fn set_dirty(self: &Self, value: bool) {
         self.contents.dirty.store(value, Ordering::Relaxed);
+        self.contents.unsorted.store(value, Ordering::Relaxed);
     }
 }
 
+/// Vertex data of a layer in drawing order.
+pub(crate) enum LayerVertices<'a> {
+    Unsorted(avec::AVecReadGuard<'a, Vertex>),
+    Sorted(MutexGuard<'a, Vec<Vertex>>),
+    Retained(MutexGuard<'a, RetainedData>),
+    Culled(MutexGuard<'a, Vec<Vertex>>),
+}
+
+impl<'a> Deref for LayerVertices<'a> {
+    type Target = [Vertex];
+
+    fn deref(&self) -> &[Vertex] {
+        match *self {
+            LayerVertices::Unsorted(ref vertices) => vertices,
+            LayerVertices::Sorted(ref vertices) => vertices,
+            LayerVertices::Culled(ref vertices) => vertices,
+            LayerVertices::Retained(ref retained) => &retained.vertices,
+        }
+    }
+}
+
+/// Returns whether the bounding box of given points intersects the area mapped to the drawing target by given view
+/// matrix.
+pub(crate) fn points_visible(points: &[Point2], view_matrix: &Mat4) -> bool {
+    let m = view_matrix;
+    let mut min = (f32::INFINITY, f32::INFINITY);
+    let mut max = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for &(x, y) in points {
+        let w = m[0][3] * x + m[1][3] * y + m[3][3];
+        let w = if w != 0.0 { w } else { 1.0 };
+        let (x, y) = ((m[0][0] * x + m[1][0] * y + m[3][0]) / w, (m[0][1] * x + m[1][1] * y + m[3][1]) / w);
+        min = (min.0.min(x), min.1.min(y));
+        max = (max.0.max(x), max.1.max(y));
+    }
+    max.0 >= -1.0 && min.0 <= 1.0 && max.1 >= -1.0 && min.1 <= 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn culled_dirty_range() {
+        let layer = Layer::new((32.0, 32.0));
+        layer.fill_rect::<Rect>(((0.0, 0.0), (8.0, 8.0)), Color::WHITE, 0.0);
+        layer.set_culling(true);
+        assert_eq!(layer.undirty(), Some((0, 4)));
+        // unchanged contents and matrices keep the culled vertices
+        assert_eq!(layer.undirty(), None);
+
+        // moving the view may change the culled set
+        layer.view_matrix()[3][0] -= 1.0;
+        assert_eq!(layer.undirty(), Some((0, 4)));
+        assert_eq!(layer.undirty(), None);
+
+        // clones with different matrices share the contents
+        let clone = layer.clone();
+        clone.model_matrix()[3][1] += 1.0;
+        assert_eq!(clone.undirty(), Some((0, 4)));
+        assert_eq!(layer.undirty(), Some((0, 4)));
+
+        layer.fill_rect::<Rect>(((8.0, 0.0), (8.0, 8.0)), Color::WHITE, 0.0);
+        assert_eq!(layer.undirty(), Some((0, 8)));
+
+        // vertices uploaded while culling must be replaced once it is disabled
+        layer.set_culling(false);
+        assert_eq!(layer.undirty(), Some((0, 8)));
+        assert_eq!(layer.undirty(), None);
+    }
+}
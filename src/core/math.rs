@@ -157,4 +157,282 @@ impl<T> RectTrait<T> for Rect<T> where T: Copy {
     fn as_array(self: &Self) -> [ T; 4 ] {
         [ (self.0).0, (self.0).1, (self.1).0, (self.1).1 ]
     }
-}
\ No newline at end of file
+}
+
+/// A 2d camera that produces view matrices for [`Layer::set_view_matrix()`](struct.Layer.html#method.set_view_matrix).
+///
+/// The camera centers its position on a viewport of the given size in pixels, usually the dimensions of the display.
+/// Positions are given in world coordinates, i.e. the coordinates sprites are drawn at. Zooming in and rotating
+/// happen around the center of the viewport.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use radiant_rs::*;
+/// # let display = Display::builder().hidden().build().unwrap();
+/// # let layer = Layer::new((640., 480.));
+/// # let (player_position, frame_time) = ((100., 100.), 0.016);
+/// let mut camera = Camera2D::new((640., 480.));
+/// camera.set_zoom(2.0).set_bounds(Some(((0., 0.), (4096., 4096.))));
+///
+/// // each frame
+/// camera.follow(player_position, 5.0, frame_time);
+/// camera.update(frame_time);
+/// layer.set_view_matrix(camera.matrix());
+///
+/// let (mouse_x, mouse_y) = display.input().mouse();
+/// let cursor = camera.screen_to_world((mouse_x as f32, mouse_y as f32));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Camera2D {
+    position        : Point2,
+    zoom            : f32,
+    rotation        : f32,
+    viewport        : Point2,
+    bounds          : Option<Rect>,
+    shake_intensity : f32,
+    shake_duration  : f32,
+    shake_remaining : f32,
+    shake_offset    : Point2,
+    shake_seed      : u32,
+}
+
+impl Camera2D {
+
+    /// Creates a new camera for a viewport of given size. The camera is initially centered on the viewport, which
+    /// produces the same view matrix as a layer created with the viewport's dimensions.
+    pub fn new<T>(viewport: T) -> Self where Point2: From<T> {
+        let viewport = Point2::from(viewport);
+        Camera2D {
+            position        : (viewport.0 / 2.0, viewport.1 / 2.0),
+            zoom            : 1.0,
+            rotation        : 0.0,
+            viewport        : viewport,
+            bounds          : None,
+            shake_intensity : 0.0,
+            shake_duration  : 0.0,
+            shake_remaining : 0.0,
+            shake_offset    : (0.0, 0.0),
+            shake_seed      : 0x9e37_79b9,
+        }
+    }
+
+    /// Sets the world position shown at the center of the viewport.
+    pub fn set_position<T>(self: &mut Self, position: T) -> &mut Self where Point2: From<T> {
+        self.position = Point2::from(position);
+        self.clamp()
+    }
+
+    /// Returns the world position shown at the center of the viewport.
+    pub fn position(self: &Self) -> Point2 {
+        self.position
+    }
+
+    /// Sets the zoom factor. Values greater than 1.0 enlarge the world.
+    pub fn set_zoom(self: &mut Self, zoom: f32) -> &mut Self {
+        assert!(zoom > 0.0, "Zoom factor must be positive.");
+        self.zoom = zoom;
+        self.clamp()
+    }
+
+    /// Returns the zoom factor.
+    pub fn zoom(self: &Self) -> f32 {
+        self.zoom
+    }
+
+    /// Sets the camera rotation in radians. The world appears rotated in the opposite direction.
+    pub fn set_rotation(self: &mut Self, rotation: f32) -> &mut Self {
+        self.rotation = rotation;
+        self.clamp()
+    }
+
+    /// Returns the camera rotation in radians.
+    pub fn rotation(self: &Self) -> f32 {
+        self.rotation
+    }
+
+    /// Sets the size of the viewport in pixels, e.g. after the display was resized.
+    pub fn set_viewport<T>(self: &mut Self, viewport: T) -> &mut Self where Point2: From<T> {
+        self.viewport = Point2::from(viewport);
+        self.clamp()
+    }
+
+    /// Returns the size of the viewport in pixels.
+    pub fn viewport(self: &Self) -> Point2 {
+        self.viewport
+    }
+
+    /// Confines the visible area to given world rectangle `((x, y), (width, height))`. If the rectangle is smaller
+    /// than the visible area, the camera is centered on it. Screen shake may briefly reveal areas outside of it.
+    pub fn set_bounds(self: &mut Self, bounds: Option<Rect>) -> &mut Self {
+        self.bounds = bounds;
+        self.clamp()
+    }
+
+    /// Returns the world rectangle the visible area is confined to.
+    pub fn bounds(self: &Self) -> Option<Rect> {
+        self.bounds
+    }
+
+    /// Moves the camera towards given world position. `speed` is the fraction of the remaining distance
+    /// covered per second, `delta` the time in seconds since the last call. The movement is independent of the frame rate.
+    pub fn follow<T>(self: &mut Self, target: T, speed: f32, delta: f32) -> &mut Self where Point2: From<T> {
+        let target = Point2::from(target);
+        let factor = 1.0 - (-speed * delta).exp();
+        let position = (
+            self.position.0 + (target.0 - self.position.0) * factor,
+            self.position.1 + (target.1 - self.position.1) * factor,
+        );
+        self.set_position::<Point2>(position)
+    }
+
+    /// Starts shaking the camera by up to `intensity` pixels for `duration` seconds. The shake fades out over time
+    /// and is advanced by [`update()`](#method.update).
+    pub fn shake(self: &mut Self, intensity: f32, duration: f32) -> &mut Self {
+        self.shake_intensity = intensity;
+        self.shake_duration = duration;
+        self.shake_remaining = duration;
+        self
+    }
+
+    /// Advances the screen shake by `delta` seconds.
+    pub fn update(self: &mut Self, delta: f32) -> &mut Self {
+        self.shake_remaining = (self.shake_remaining - delta).max(0.0);
+        self.shake_offset = if self.shake_remaining > 0.0 {
+            let strength = self.shake_intensity * self.shake_remaining / self.shake_duration;
+            let angle = self.next_random() * 2.0 * f32::consts::PI;
+            let distance = self.next_random() * strength;
+            (angle.cos() * distance, angle.sin() * distance)
+        } else {
+            (0.0, 0.0)
+        };
+        self
+    }
+
+    /// Returns the view matrix for the current camera state.
+    pub fn matrix(self: &Self) -> Mat4 {
+        let (width, height) = self.viewport;
+        let (center_x, center_y) = self.center();
+        let (sin, cos) = self.rotation.sin_cos();
+        // world to screen: rotate by -rotation and scale around the center
+        let (a, b, c, d) = (self.zoom * cos, self.zoom * sin, -self.zoom * sin, self.zoom * cos);
+        let translate_x = width / 2.0 - (a * center_x + b * center_y);
+        let translate_y = height / 2.0 - (c * center_x + d * center_y);
+        // screen to clip space
+        [
+            [ 2.0 * a / width, -2.0 * c / height, 0., 0. ],
+            [ 2.0 * b / width, -2.0 * d / height, 0., 0. ],
+            [ 0., 0., 1., 0. ],
+            [ 2.0 * translate_x / width - 1.0, -2.0 * translate_y / height + 1.0, 0., 1. ],
+        ]
+    }
+
+    /// Converts given viewport position in pixels, e.g. from [`Input::mouse()`](struct.Input.html#method.mouse),
+    /// into world coordinates.
+    pub fn screen_to_world<T>(self: &Self, position: T) -> Point2 where Point2: From<T> {
+        let position = Point2::from(position);
+        let (center_x, center_y) = self.center();
+        let (sin, cos) = self.rotation.sin_cos();
+        let x = (position.0 - self.viewport.0 / 2.0) / self.zoom;
+        let y = (position.1 - self.viewport.1 / 2.0) / self.zoom;
+        (center_x + x * cos - y * sin, center_y + x * sin + y * cos)
+    }
+
+    /// Converts given world position into a viewport position in pixels.
+    pub fn world_to_screen<T>(self: &Self, position: T) -> Point2 where Point2: From<T> {
+        let position = Point2::from(position);
+        let (center_x, center_y) = self.center();
+        let (sin, cos) = self.rotation.sin_cos();
+        let (x, y) = (position.0 - center_x, position.1 - center_y);
+        (
+            self.viewport.0 / 2.0 + (x * cos + y * sin) * self.zoom,
+            self.viewport.1 / 2.0 + (y * cos - x * sin) * self.zoom,
+        )
+    }
+
+    /// Returns the shaken world position at the center of the viewport.
+    fn center(self: &Self) -> Point2 {
+        (self.position.0 + self.shake_offset.0, self.position.1 + self.shake_offset.1)
+    }
+
+    /// Moves the camera back into its bounds.
+    fn clamp(self: &mut Self) -> &mut Self {
+        if let Some(((x, y), (width, height))) = self.bounds {
+            // half extents of the visible area in world space, enlarged by the rotation
+            let (sin, cos) = self.rotation.sin_cos();
+            let half_width = (cos.abs() * self.viewport.0 + sin.abs() * self.viewport.1) / (2.0 * self.zoom);
+            let half_height = (sin.abs() * self.viewport.0 + cos.abs() * self.viewport.1) / (2.0 * self.zoom);
+            let clamp = |position: f32, start: f32, size: f32, half_extent: f32| {
+                if size <= half_extent * 2.0 {
+                    start + size / 2.0
+                } else {
+                    position.max(start + half_extent).min(start + size - half_extent)
+                }
+            };
+            self.position = (clamp(self.position.0, x, width, half_width), clamp(self.position.1, y, height, half_height));
+        }
+        self
+    }
+
+    /// Returns a pseudo random number in [0, 1).
+    fn next_random(self: &mut Self) -> f32 {
+        // xorshift32
+        let mut seed = self.shake_seed;
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        self.shake_seed = seed;
+        (seed >> 8) as f32 / (1 << 24) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: Point2, b: Point2) {
+        assert!((a.0 - b.0).abs() < 0.001 && (a.1 - b.1).abs() < 0.001, "{:?} != {:?}", a, b);
+    }
+
+    fn to_clip(matrix: &Mat4, position: Point2) -> Point2 {
+        (matrix[0][0] * position.0 + matrix[1][0] * position.1 + matrix[3][0], matrix[0][1] * position.0 + matrix[1][1] * position.1 + matrix[3][1])
+    }
+
+    #[test]
+    fn camera() {
+        let mut camera = Camera2D::new((640., 480.));
+        assert_eq!(camera.matrix(), Mat4::viewport(640., 480.));
+
+        camera.set_position((1000., 500.)).set_zoom(2.0).set_rotation(0.5);
+        assert_near(to_clip(&camera.matrix(), (1000., 500.)), (0., 0.));
+        assert_near(camera.screen_to_world((320., 240.)), (1000., 500.));
+        for &position in [ (0., 0.), (100., -50.), (640., 480.) ].iter() {
+            assert_near(camera.world_to_screen(camera.screen_to_world(position)), position);
+            // screen coordinates map to the same clip space position as the world position
+            let world = camera.screen_to_world(position);
+            assert_near(to_clip(&camera.matrix(), world), to_clip(&Mat4::viewport(640., 480.), position));
+        }
+
+        // a point to the right of the center appears below it after rotating the camera by a quarter turn
+        camera.set_rotation(-f32::consts::PI / 2.0).set_zoom(1.0);
+        assert_near(camera.world_to_screen((1010., 500.)), (320., 250.));
+
+        camera.set_rotation(0.0).set_bounds(Some(((0., 0.), (1000., 1000.))));
+        assert_near(camera.position(), (680., 500.));
+        camera.set_position((-100., -100.));
+        assert_near(camera.position(), (320., 240.));
+        // the bounds are narrower than the visible area
+        camera.set_zoom(0.5);
+        assert_near(camera.position(), (500., 480.));
+
+        camera.set_bounds(None).set_position((0., 0.)).follow((100., 0.), 2.0, 0.5);
+        assert_near(camera.position(), (100. * (1. - (-1.0f32).exp()), 0.));
+
+        camera.shake(10.0, 1.0).update(0.5);
+        let offset = camera.screen_to_world((320., 240.));
+        let distance = ((offset.0 - camera.position().0).powi(2) + (offset.1 - camera.position().1).powi(2)).sqrt();
+        assert!(distance <= 5.0);
+        camera.update(0.5);
+        assert_near(camera.screen_to_world((320., 240.)), camera.position());
+    }
+}
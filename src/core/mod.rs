@@ -4,7 +4,10 @@ mod input;
 mod layer;
 mod renderer;
 mod sprite;
+mod animation;
+mod tilemap;
 mod font;
+mod richtext;
 mod context;
 mod color;
 mod monitor;
@@ -15,13 +18,20 @@ mod postprocessor;
 mod builder;
 mod rendertarget;
 mod math;
+mod testing;
+mod recorder;
+mod atlas;
+mod sheet;
 
 pub use self::blendmode::*;
 pub use self::input::*;
 pub use self::display::*;
 pub use self::sprite::*;
+pub use self::animation::*;
+pub use self::tilemap::*;
 pub use self::renderer::*;
 pub use self::font::*;
+pub use self::richtext::*;
 pub use self::layer::*;
 pub use self::context::*;
 pub use self::color::*;
@@ -33,6 +43,9 @@ pub use self::postprocessor::*;
 pub use self::builder::*;
 pub use self::rendertarget::*;
 pub use self::math::*;
+pub use self::testing::*;
+pub use self::recorder::*;
+pub use self::atlas::*;
 use image;
 use prelude::*;
 use backends::backend;
@@ -48,6 +61,11 @@ pub struct Vertex {
     pub texture_id  : u32,
     pub texture_uv  : [f32; 2],
     pub components  : u32,
+    /// Sort key, not passed to the shader.
+    pub z           : f32,
+    /// Number of quads drawn by the draw call starting at this vertex, 0 for the remaining vertices of a draw call.
+    /// Not passed to the shader.
+    pub draw_quads  : u32,
 }
 
 /// Radiant errors.
@@ -58,6 +76,10 @@ pub enum Error {
     IoError(io::Error),
     FullscreenError(String),
     FontError(String),
+    SpriteSizeError(String),
+    SpriteLayoutError(String),
+    TileMapError(String),
+    TextMarkupError(String),
     BackendError(backend::Error),
     Failed,
 }
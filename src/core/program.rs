@@ -1,5 +1,5 @@
 use prelude::*;
-use core::{self, Context, AsUniform, UniformList, Color};
+use core::{self, context, Context, AsUniform, UniformList, Color};
 use core::math::*;
 use backends::backend;
 
@@ -50,7 +50,7 @@ impl Program {
     }
     /// Creates a new program. Used in context creation when the full context is not yet available.
     pub(crate) fn new(context: &Context, source: &str) -> core::Result<Program> {
-        let sprite_fs = Self::insert_template(source, SPRITE_INC);
+        let sprite_fs = Self::insert_template(source, &Self::sprite_template());
         let texture_fs = Self::insert_template(source, TEXTURE_INC);
         let mut uniforms = UniformList::new();
         uniforms.insert("u_view", Mat4::viewport(1.0, 1.0).as_uniform());
@@ -64,6 +64,22 @@ impl Program {
             texture_program: Arc::new(backend::Program::new(backend_context, TEXTURE_VS, &texture_fs)?),
         })
     }
+    /// Expands the texture bucket placeholders of the sprite template into a sampler and branches per bucket.
+    fn sprite_template() -> String {
+        let mut samplers = String::new();
+        let mut sizes = String::new();
+        let mut texels = String::new();
+        for bucket_id in 1..context::NUM_BUCKETS {
+            let sampler = context::BUCKET_SAMPLERS[bucket_id];
+            samplers.push_str(&format!("uniform sampler2DArray {};\n", sampler));
+            sizes.push_str(&format!("    }} else if (_rd_v_bucket_id == {}u) {{\n        return textureSize({}, 0).xy;\n", bucket_id, sampler));
+            texels.push_str(&format!("    }} else if (_rd_v_bucket_id == {}u) {{\n        return texture({}, vec3(texture_coords, float(_rd_v_texture_id + component)));\n", bucket_id, sampler));
+        }
+        SPRITE_INC
+            .replace("_RD_BUCKET_SAMPLERS\n", &samplers)
+            .replace("_RD_BUCKET_SIZES\n", &sizes)
+            .replace("_RD_BUCKET_TEXELS\n", &texels)
+    }
     /// Inserts program boilterplate code into the shader source.
     fn insert_template(source: &str, template: &str) -> String {
         let mut result = String::new();
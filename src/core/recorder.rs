@@ -0,0 +1,74 @@
+use core::{Result, Error, Texture};
+use image;
+
+/// Captures a sequence of frames to numbered PNG files.
+///
+/// A recorder can be attached to a [`Display`](struct.Display.html) using
+/// [`Display::start_recording()`](struct.Display.html#method.start_recording) to capture swapped frames, or
+/// be fed manually, e.g. with the contents of a texture used as target for
+/// [`Renderer::render_to()`](struct.Renderer.html#method.render_to).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use radiant_rs::*;
+/// # let display = Display::builder().hidden().build().unwrap();
+/// // capture every second frame to captures/frame00000.png, captures/frame00001.png, ...
+/// display.start_recording(Recorder::new("captures/frame", 2));
+///
+/// for _ in 0..60 {
+///     display.clear_frame(Color::BLACK);
+///     // ...
+///     display.swap_frame();
+/// }
+///
+/// let num_written = display.stop_recording().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Recorder {
+    prefix      : String,
+    interval    : u32,
+    num_seen    : u32,
+    num_written : u32,
+    pub(crate) error: Option<Error>,
+}
+
+impl Recorder {
+
+    /// Creates a new recorder that captures every `interval`th frame. Files are named `<prefix><number>.png`,
+    /// with the number padded to five digits.
+    pub fn new(prefix: &str, interval: u32) -> Self {
+        assert!(interval > 0, "Recorder interval must be at least 1.");
+        Recorder {
+            prefix      : prefix.to_string(),
+            interval    : interval,
+            num_seen    : 0,
+            num_written : 0,
+            error       : None,
+        }
+    }
+
+    /// Counts a frame and, if it is due for capture, writes the image returned by `read_func()` to the next file.
+    /// Returns whether the frame was captured.
+    pub fn capture<F>(self: &mut Self, read_func: F) -> Result<bool> where F: FnOnce() -> image::RgbaImage {
+        let due = self.num_seen % self.interval == 0;
+        self.num_seen += 1;
+        if due {
+            let file = format!("{}{:05}.png", self.prefix, self.num_written);
+            read_func().save(&file)?;
+            self.num_written += 1;
+        }
+        Ok(due)
+    }
+
+    /// Counts a frame and, if it is due for capture, writes the contents of the given texture to the next file.
+    /// Returns whether the frame was captured.
+    pub fn capture_texture(self: &mut Self, texture: &Texture) -> Result<bool> {
+        self.capture(|| texture.read_pixels())
+    }
+
+    /// Returns the number of files written so far.
+    pub fn num_written(self: &Self) -> u32 {
+        self.num_written
+    }
+}
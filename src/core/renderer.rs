@@ -1,7 +1,7 @@
 use prelude::*;
 use core::{
     self, context,
-    Display, Layer, Texture, TextureFilter, Color, Program, Postprocessor,
+    Display, Layer, TileMap, Texture, TextureFilter, Color, Program, Postprocessor,
     Context, AsRenderTarget, RenderTarget, RenderTargetInner,
     blendmodes, TextureFormat
 };
@@ -80,6 +80,17 @@ impl Renderer {
     /// All sprites support at least component 0. Sprites that do not support
     /// the given component will not be drawn.
     pub fn draw_layer(self: &Self, layer: &Layer, component: u32) -> &Self {
+        self.draw_layer_texture(layer, component, None)
+    }
+
+    /// Draws given layer to the current target, using the given texture for meshes drawn with
+    /// [`Layer::draw_textured_mesh()`](struct.Layer.html#method.draw_textured_mesh). The texture must not be the current target.
+    pub fn draw_layer_with_texture(self: &Self, layer: &Layer, component: u32, texture: &Texture) -> &Self {
+        self.draw_layer_texture(layer, component, Some(texture))
+    }
+
+    /// Draws given layer to the current target.
+    fn draw_layer_texture(self: &Self, layer: &Layer, component: u32, texture: Option<&Texture>) -> &Self {
 
         // open context
         let mut context = self.context.lock();
@@ -92,7 +103,16 @@ impl Renderer {
         // use default or custom program
         let program = layer.program().unwrap_or(&self.program);
 
-        backend::draw_layer(self.target.borrow().last().unwrap(), program, context, layer, component);
+        backend::draw_layer(self.target.borrow().last().unwrap(), program, context, layer, component, texture);
+        self
+    }
+
+    /// Draws the chunks of given tile map that intersect its view to the current target. Chunks whose tiles changed
+    /// since they were last drawn are rebuilt first.
+    pub fn draw_tilemap(self: &Self, tilemap: &TileMap, component: u32) -> &Self {
+        for layer in tilemap.visible_layers() {
+            self.draw_layer(layer, component);
+        }
         self
     }
 
@@ -259,14 +279,22 @@ impl Renderer {
         self
     }
 
-    /// Returns the appropriate bucket_id and padded texture size for the given texture size
-    pub(crate) fn bucket_info(width: u32, height: u32) -> (u32, u32) {
+    /// Returns the appropriate bucket_id and padded texture size for the given texture size. Fails with
+    /// `Error::SpriteSizeError` if the padded size exceeds the largest bucket or given maximum texture size.
+    pub(crate) fn bucket_info(width: u32, height: u32, max_texture_size: u32) -> core::Result<(u32, u32)> {
         let ln2 = (cmp::max(width, height) as f32).log2().ceil() as u32;
         // skip first five sizes 1x1 to 16x16, use id 0 for font-cache
         let bucket_id = cmp::max(1, ln2 as i32 - 4 + 1) as u32;
-        let size = 2u32.pow(bucket_id + 4 - 1);
-        assert!(bucket_id < context::NUM_BUCKETS as u32, "texture size exceeded configured maximum");
-        (bucket_id, size)
+        if bucket_id >= context::NUM_BUCKETS as u32 || Self::bucket_size(bucket_id) > max_texture_size {
+            let max_size = (1..context::NUM_BUCKETS as u32).map(Self::bucket_size).take_while(|&size| size <= max_texture_size).last().unwrap_or(0);
+            return Err(core::Error::SpriteSizeError(format!("Sprite frame of {}x{} pixels exceeds maximum frame size of {}x{} pixels", width, height, max_size, max_size)));
+        }
+        Ok((bucket_id, Self::bucket_size(bucket_id)))
+    }
+
+    /// Returns the texture size of given bucket.
+    fn bucket_size(bucket_id: u32) -> u32 {
+        2u32.pow(bucket_id + 4 - 1)
     }
 
     /// Pushes a target onto the target stack
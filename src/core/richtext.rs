@@ -0,0 +1,188 @@
+use prelude::*;
+use core::{self, Font, Layer, Color, TextBuilder, TextMetrics, TextGlyph};
+use core::math::*;
+
+/// Text composed of spans that differ in font, size or color.
+///
+/// Spans are either appended using [`push()`](#method.push) or parsed from markup using
+/// [`from_markup()`](#method.from_markup). The text is laid out across all spans, so that lines wrap and align as a
+/// whole. All fonts share the glyph cache of their context and should belong to the same context.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use radiant_rs::*;
+/// # let display = Display::builder().hidden().build().unwrap();
+/// # let layer = Layer::new((640., 480.));
+/// # let font = Font::builder(display.context()).family("Arial").size(16.0).build().unwrap();
+/// # let bold = Font::builder(display.context()).family("Arial").bold().size(16.0).build().unwrap();
+/// let text = RichText::from_markup(
+///     "You found [color=#ffd700]120 gold[/color] and a [font=bold][size=24]key[/size][/font].",
+///     &font, Color::WHITE, &[ ("bold", &bold) ]
+/// ).unwrap();
+///
+/// text.write_builder(&layer).position((20., 20.)).max_width(300.).write();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RichText {
+    spans: Vec<(Font, Color, String)>,
+}
+
+impl RichText {
+
+    /// Creates a new, empty instance.
+    pub fn new() -> Self {
+        RichText {
+            spans: Vec::new(),
+        }
+    }
+
+    /// Appends a span of text written with given font and color.
+    pub fn push(self: &mut Self, font: &Font, color: Color, text: &str) -> &mut Self {
+        self.spans.push((font.clone(), color, text.to_string()));
+        self
+    }
+
+    /// Parses given markup. Text outside of tags is written with given font and color.
+    ///
+    /// Supported tags are `[color=#rrggbb]` (also `#rgb`, `#rgba` and `#rrggbbaa`), `[size=24]` and `[font=name]`,
+    /// where `name` refers to one of the given named fonts. Each tag is closed by `[/color]`, `[/size]` or `[/font]`
+    /// respectively and tags may be nested. Tags left open extend to the end of the text. Use `[[` to write a
+    /// literal `[`.
+    pub fn from_markup(markup: &str, font: &Font, color: Color, fonts: &[(&str, &Font)]) -> core::Result<Self> {
+
+        let mut result = Self::new();
+        let mut colors = vec![ color ];
+        let mut fonts_used = vec![ font.clone() ];
+        let mut sizes: Vec<f32> = Vec::new();
+        let mut text = String::new();
+        let mut rest = markup;
+
+        while let Some(start) = rest.find('[') {
+            text.push_str(&rest[..start]);
+            rest = &rest[start + 1..];
+            if rest.starts_with('[') {
+                text.push('[');
+                rest = &rest[1..];
+                continue;
+            }
+            let end = rest.find(']').ok_or_else(|| markup_error(format!("Unterminated tag at \"[{}\"", rest)))?;
+            let tag = &rest[..end];
+            rest = &rest[end + 1..];
+
+            // finish the span written with the previous style
+            if text.len() > 0 {
+                result.push_styled(fonts_used.last().unwrap(), &sizes, *colors.last().unwrap(), &text);
+                text.clear();
+            }
+
+            let (name, value) = match tag.find('=') {
+                Some(position) => (&tag[..position], Some(&tag[position + 1..])),
+                None => (tag, None),
+            };
+            match (name, value) {
+                ("color", Some(value)) => colors.push(parse_color(value).ok_or_else(|| markup_error(format!("Invalid color \"{}\"", value)))?),
+                ("size", Some(value)) => sizes.push(parse_size(value).ok_or_else(|| markup_error(format!("Invalid size \"{}\"", value)))?),
+                ("font", Some(value)) => {
+                    let &(_, named_font) = fonts.iter().find(|&&(font_name, _)| font_name == value).ok_or_else(|| markup_error(format!("Unknown font \"{}\"", value)))?;
+                    fonts_used.push(named_font.clone());
+                }
+                ("/color", None) if colors.len() > 1 => { colors.pop(); }
+                ("/size", None) if sizes.len() > 0 => { sizes.pop(); }
+                ("/font", None) if fonts_used.len() > 1 => { fonts_used.pop(); }
+                _ => return Err(markup_error(format!("Unexpected tag \"[{}]\"", tag))),
+            }
+        }
+
+        text.push_str(rest);
+        if text.len() > 0 {
+            result.push_styled(fonts_used.last().unwrap(), &sizes, *colors.last().unwrap(), &text);
+        }
+        Ok(result)
+    }
+
+    /// Measures the text without drawing it. Breaks lines after max_width pixels unless max_width is 0.0.
+    /// See [`Font::measure()`](struct.Font.html#method.measure).
+    pub fn measure(self: &Self, max_width: f32) -> TextMetrics {
+        Font::measure_runs(&self.runs(Color::WHITE), max_width)
+    }
+
+    /// Measures the text without drawing it and returns the rectangle occupied by each character. Breaks lines
+    /// after max_width pixels unless max_width is 0.0.
+    pub fn measure_glyphs(self: &Self, max_width: f32) -> Vec<TextGlyph> {
+        Font::measure_run_glyphs(&self.runs(Color::WHITE), max_width)
+    }
+
+    /// Writes the text to given layer.
+    pub fn write<T>(self: &Self, layer: &Layer, position: T) -> &Self where Point2: From<T> {
+        self.write_builder(layer).position(position).write();
+        self
+    }
+
+    /// Returns a [text writing builder](support/struct.TextBuilder.html) for writing the text onto the given layer
+    /// with additional options like alignment, rotation and scaling. The builder's color is multiplied with the
+    /// colors of the spans.
+    pub fn write_builder<'a>(self: &'a Self, layer: &'a Layer) -> TextBuilder<'a> {
+        TextBuilder::from_rich_text(self, layer)
+    }
+
+    /// Returns the spans as runs of font, color and text for layouting, multiplying their colors with given color.
+    pub(crate) fn runs(self: &Self, color: Color) -> Vec<(&Font, Color, &str)> {
+        self.spans.iter().map(|&(ref font, span_color, ref text)| {
+            (font, Color(span_color.0 * color.0, span_color.1 * color.1, span_color.2 * color.2, span_color.3 * color.3), &text[..])
+        }).collect()
+    }
+
+    /// Appends a span using given font at the size on top of the size stack.
+    fn push_styled(self: &mut Self, font: &Font, sizes: &[f32], color: Color, text: &str) {
+        let font = match sizes.last() {
+            Some(&size) => font.clone_with_size(size),
+            None => font.clone(),
+        };
+        self.spans.push((font, color, text.to_string()));
+    }
+}
+
+/// Creates a markup error.
+fn markup_error(message: String) -> core::Error {
+    core::Error::TextMarkupError(message)
+}
+
+/// Parses a positive font size.
+fn parse_size(value: &str) -> Option<f32> {
+    match value.parse::<f32>() {
+        Ok(size) if size > 0.0 => Some(size),
+        _ => None,
+    }
+}
+
+/// Parses a hexadecimal color of the form #rgb, #rgba, #rrggbb or #rrggbbaa.
+fn parse_color(value: &str) -> Option<Color> {
+    if !value.starts_with('#') {
+        return None;
+    }
+    let digits = value[1..].chars().map(|c| c.to_digit(16)).collect::<Option<Vec<u32>>>()?;
+    let channels: Vec<f32> = match digits.len() {
+        3 | 4 => digits.iter().map(|&digit| (digit * 17) as f32 / 255.0).collect(),
+        6 | 8 => digits.chunks(2).map(|pair| (pair[0] * 16 + pair[1]) as f32 / 255.0).collect(),
+        _ => return None,
+    };
+    Some(Color(channels[0], channels[1], channels[2], channels.get(3).cloned().unwrap_or(1.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors() {
+        assert_eq!(parse_color("#ff0"), Some(Color(1.0, 1.0, 0.0, 1.0)));
+        assert_eq!(parse_color("#ff000080"), Some(Color(1.0, 0.0, 0.0, 128.0 / 255.0)));
+        assert_eq!(parse_color("#0000ff"), Some(Color(0.0, 0.0, 1.0, 1.0)));
+        assert_eq!(parse_color("#f0f8"), Some(Color(1.0, 0.0, 1.0, 136.0 / 255.0)));
+        assert_eq!(parse_color("ff0"), None);
+        assert_eq!(parse_color("#ff00"), Some(Color(1.0, 1.0, 0.0, 0.0)));
+        assert_eq!(parse_color("#ff00f"), None);
+        assert_eq!(parse_color("#ggg"), None);
+    }
+}
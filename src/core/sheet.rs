@@ -0,0 +1,283 @@
+use core::{self, Point2};
+use serde_json::{self, Value};
+
+/// Location and metadata of a single frame on a sprite sheet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SheetFrame {
+    /// Top/left position of each component of the frame.
+    pub positions   : Vec<Point2<u32>>,
+    /// Width and height of the frame.
+    pub dimensions  : Point2<u32>,
+    /// Default anchor, relative to the frame dimensions.
+    pub pivot       : Point2<f32>,
+    /// Display duration in seconds.
+    pub duration    : Option<f32>,
+    /// Name of the frame.
+    pub name        : Option<String>,
+    /// Nine-slice border insets (left, top, right, bottom) in pixels.
+    pub slice       : Option<(u32, u32, u32, u32)>,
+}
+
+/// Contents of a sprite sheet descriptor file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SheetDescriptor {
+    /// Path of the sheet image, relative to the descriptor.
+    pub image   : String,
+    /// Frames in order of appearance.
+    pub frames  : Vec<SheetFrame>,
+}
+
+impl SheetDescriptor {
+
+    /// Parses a sheet descriptor. Supports the native format described at `Sprite::from_sheet_descriptor()` as well
+    /// as TexturePacker and Aseprite JSON (hash or array variants).
+    pub fn parse(source: &str) -> core::Result<Self> {
+
+        let json: Value = serde_json::from_str(source).map_err(|error| descriptor_error(format!("Invalid JSON: {}", error)))?;
+
+        if let Some(image) = json.get("image") {
+            Self::parse_native(image, json.get("frames"))
+        } else if let Some(meta) = json.get("meta") {
+            Self::parse_texturepacker(meta, json.get("frames"))
+        } else {
+            Err(descriptor_error("Unknown sprite sheet descriptor format".to_string()))
+        }
+    }
+
+    /// Parses the native descriptor format.
+    fn parse_native(image: &Value, frames: Option<&Value>) -> core::Result<Self> {
+
+        let image = image.as_str().ok_or_else(|| descriptor_error("Expected string for image".to_string()))?;
+        let frames = frames.and_then(|frames| frames.as_array()).ok_or_else(|| descriptor_error("Expected array of frames".to_string()))?;
+        let mut result = Vec::new();
+
+        for frame in frames.iter() {
+            let pivot = match frame.get("pivot").and_then(|pivot| pivot.as_array()) {
+                Some(pivot) if pivot.len() == 2 => (number(&pivot[0], "pivot")?, number(&pivot[1], "pivot")?),
+                Some(_) => return Err(descriptor_error("Expected pivot of two numbers".to_string())),
+                None => (0.5, 0.5),
+            };
+            let slice = match frame.get("slice").and_then(|slice| slice.as_array()) {
+                Some(slice) if slice.len() == 4 => Some((integer(Some(&slice[0]), "slice")?, integer(Some(&slice[1]), "slice")?, integer(Some(&slice[2]), "slice")?, integer(Some(&slice[3]), "slice")?)),
+                Some(_) => return Err(descriptor_error("Expected slice of four integers".to_string())),
+                None => None,
+            };
+            result.push(SheetFrame {
+                positions   : vec![ (integer(frame.get("x"), "x")?, integer(frame.get("y"), "y")?) ],
+                dimensions  : (integer(frame.get("w"), "w")?, integer(frame.get("h"), "h")?),
+                pivot       : pivot,
+                duration    : duration(frame.get("duration"))?,
+                name        : frame.get("name").and_then(|name| name.as_str()).map(|name| name.to_string()),
+                slice       : slice,
+            });
+        }
+
+        Ok(SheetDescriptor {
+            image   : image.to_string(),
+            frames  : result,
+        })
+    }
+
+    /// Parses TexturePacker and Aseprite JSON (hash or array).
+    fn parse_texturepacker(meta: &Value, frames: Option<&Value>) -> core::Result<Self> {
+
+        let image = meta.get("image").and_then(|image| image.as_str()).ok_or_else(|| descriptor_error("Expected string for meta.image".to_string()))?;
+
+        // hash variant uses the frame name as key, array variant has a filename member
+        let entries: Vec<(Option<&str>, &Value)> = match frames {
+            Some(&Value::Object(ref members)) => members.iter().map(|(name, frame)| (Some(name.as_str()), frame)).collect(),
+            Some(&Value::Array(ref frames)) => frames.iter().map(|frame| (frame.get("filename").and_then(|name| name.as_str()), frame)).collect(),
+            _ => return Err(descriptor_error("Expected object or array of frames".to_string())),
+        };
+
+        let mut result = Vec::new();
+
+        for (name, entry) in entries {
+
+            if entry.get("rotated").and_then(|rotated| rotated.as_bool()).unwrap_or(false) {
+                return Err(descriptor_error(format!("Rotated frame {} is not supported", name.unwrap_or("(unnamed)"))));
+            }
+
+            let frame = entry.get("frame").ok_or_else(|| descriptor_error("Expected frame rectangle".to_string()))?;
+            let (x, y) = (integer(frame.get("x"), "frame.x")?, integer(frame.get("y"), "frame.y")?);
+            let (width, height) = (integer(frame.get("w"), "frame.w")?, integer(frame.get("h"), "frame.h")?);
+
+            let pivot = match entry.get("pivot") {
+                Some(pivot) => (number(pivot.get("x").unwrap_or(&Value::Null), "pivot.x")?, number(pivot.get("y").unwrap_or(&Value::Null), "pivot.y")?),
+                None => (0.5, 0.5),
+            };
+
+            // trimmed frames: convert pivot from untrimmed source space to trimmed frame space
+            let pivot = match (entry.get("spriteSourceSize"), entry.get("sourceSize")) {
+                (Some(trimmed), Some(source)) if width > 0 && height > 0 => {
+                    let offset = (integer(trimmed.get("x"), "spriteSourceSize.x")? as f32, integer(trimmed.get("y"), "spriteSourceSize.y")? as f32);
+                    let source = (integer(source.get("w"), "sourceSize.w")? as f32, integer(source.get("h"), "sourceSize.h")? as f32);
+                    ((pivot.0 * source.0 - offset.0) / width as f32, (pivot.1 * source.1 - offset.1) / height as f32)
+                }
+                _ => pivot,
+            };
+
+            // TexturePacker scale9 borders give the rectangle of the center slice
+            let slice = match entry.get("scale9Borders") {
+                Some(borders) if entry.get("scale9Enabled").and_then(|enabled| enabled.as_bool()).unwrap_or(true) => {
+                    let (left, top) = (integer(borders.get("x"), "scale9Borders.x")?, integer(borders.get("y"), "scale9Borders.y")?);
+                    let (center_width, center_height) = (integer(borders.get("w"), "scale9Borders.w")?, integer(borders.get("h"), "scale9Borders.h")?);
+                    let right = left.checked_add(center_width).and_then(|inner| width.checked_sub(inner));
+                    let bottom = top.checked_add(center_height).and_then(|inner| height.checked_sub(inner));
+                    match (right, bottom) {
+                        (Some(right), Some(bottom)) => Some((left, top, right, bottom)),
+                        _ => return Err(descriptor_error(format!("scale9Borders of frame {} exceed the frame", name.unwrap_or("(unnamed)")))),
+                    }
+                }
+                _ => None,
+            };
+
+            result.push(SheetFrame {
+                positions   : vec![ (x, y) ],
+                dimensions  : (width, height),
+                pivot       : pivot,
+                duration    : duration(entry.get("duration"))?,
+                name        : name.map(|name| name.to_string()),
+                slice       : slice,
+            });
+        }
+
+        Ok(SheetDescriptor {
+            image   : image.to_string(),
+            frames  : result,
+        })
+    }
+}
+
+/// Creates a descriptor error with given message.
+fn descriptor_error(message: String) -> core::Error {
+    core::Error::SpriteLayoutError(message)
+}
+
+/// Returns given value as a non-negative integer or an error naming the field.
+fn integer(value: Option<&Value>, field: &str) -> core::Result<u32> {
+    value.and_then(|value| value.as_u64()).and_then(|value| if value <= u32::max_value() as u64 { Some(value as u32) } else { None }).ok_or_else(|| descriptor_error(format!("Expected non-negative integer for {}", field)))
+}
+
+/// Returns given value as a float or an error naming the field.
+fn number(value: &Value, field: &str) -> core::Result<f32> {
+    value.as_f64().map(|value| value as f32).ok_or_else(|| descriptor_error(format!("Expected number for {}", field)))
+}
+
+/// Converts an optional duration in milliseconds to seconds.
+fn duration(value: Option<&Value>) -> core::Result<Option<f32>> {
+    match value {
+        Some(value) => Ok(Some(number(value, "duration")? / 1000.0)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_layout_error(result: core::Result<SheetDescriptor>) {
+        match result {
+            Err(core::Error::SpriteLayoutError(_)) => { }
+            other => panic!("expected SpriteLayoutError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn native() {
+        let descriptor = SheetDescriptor::parse(r#"{ "image": "ship.png", "frames": [
+            { "name": "idle", "x": 0, "y": 0, "w": 64, "h": 48, "pivot": [ 0.5, 1.0 ], "duration": 100 },
+            { "x": 64, "y": 0, "w": 64, "h": 60 }
+        ] }"#).unwrap();
+        assert_eq!(descriptor.image, "ship.png");
+        assert_eq!(descriptor.frames.len(), 2);
+        assert_eq!(descriptor.frames[0].positions, vec![ (0, 0) ]);
+        assert_eq!(descriptor.frames[0].dimensions, (64, 48));
+        assert_eq!(descriptor.frames[0].pivot, (0.5, 1.0));
+        assert_eq!(descriptor.frames[0].duration, Some(0.1));
+        assert_eq!(descriptor.frames[0].name, Some("idle".to_string()));
+        assert_eq!(descriptor.frames[1].pivot, (0.5, 0.5));
+        assert_eq!(descriptor.frames[1].duration, None);
+        assert_eq!(descriptor.frames[1].name, None);
+        assert_eq!(descriptor.frames[1].slice, None);
+    }
+
+    #[test]
+    fn slices() {
+        let descriptor = SheetDescriptor::parse(r#"{ "image": "ui.png", "frames": [
+            { "x": 0, "y": 0, "w": 32, "h": 32, "slice": [ 4, 6, 8, 10 ] }
+        ] }"#).unwrap();
+        assert_eq!(descriptor.frames[0].slice, Some((4, 6, 8, 10)));
+
+        let descriptor = SheetDescriptor::parse(r#"{
+            "frames": { "button": { "frame": { "x": 0, "y": 0, "w": 32, "h": 24 }, "scale9Enabled": true,
+                "scale9Borders": { "x": 4, "y": 6, "w": 20, "h": 8 } } },
+            "meta": { "image": "ui.png" }
+        }"#).unwrap();
+        assert_eq!(descriptor.frames[0].slice, Some((4, 6, 8, 10)));
+
+        // borders exceeding the frame, including sums overflowing u32
+        assert_layout_error(SheetDescriptor::parse(r#"{
+            "frames": { "button": { "frame": { "x": 0, "y": 0, "w": 32, "h": 24 }, "scale9Borders": { "x": 4, "y": 6, "w": 29, "h": 8 } } },
+            "meta": { "image": "ui.png" }
+        }"#));
+        assert_layout_error(SheetDescriptor::parse(r#"{
+            "frames": { "button": { "frame": { "x": 0, "y": 0, "w": 32, "h": 24 }, "scale9Borders": { "x": 4, "y": 4294967295, "w": 20, "h": 1 } } },
+            "meta": { "image": "ui.png" }
+        }"#));
+    }
+
+    #[test]
+    fn texturepacker_array() {
+        let descriptor = SheetDescriptor::parse(r#"{
+            "frames": [
+                { "filename": "walk_0", "frame": { "x": 2, "y": 3, "w": 10, "h": 20 }, "rotated": false, "trimmed": false, "pivot": { "x": 0.5, "y": 1 } },
+                { "filename": "walk_1", "frame": { "x": 12, "y": 3, "w": 10, "h": 20 }, "rotated": false, "trimmed": false, "pivot": { "x": 0.5, "y": 1 } }
+            ],
+            "meta": { "image": "walk.png" }
+        }"#).unwrap();
+        assert_eq!(descriptor.image, "walk.png");
+        assert_eq!(descriptor.frames[1].positions, vec![ (12, 3) ]);
+        assert_eq!(descriptor.frames[1].pivot, (0.5, 1.0));
+        assert_eq!(descriptor.frames[1].name, Some("walk_1".to_string()));
+    }
+
+    #[test]
+    fn texturepacker_hash_order() {
+        let descriptor = SheetDescriptor::parse(r#"{
+            "frames": { "walk_1": { "frame": { "x": 10, "y": 0, "w": 10, "h": 20 } }, "walk_0": { "frame": { "x": 0, "y": 0, "w": 10, "h": 20 } } },
+            "meta": { "image": "walk.png" }
+        }"#).unwrap();
+        assert_eq!(descriptor.frames[0].name, Some("walk_1".to_string()));
+        assert_eq!(descriptor.frames[1].positions, vec![ (0, 0) ]);
+    }
+
+    #[test]
+    fn trimmed_pivot() {
+        // 10x10 frame trimmed from a 20x20 source at offset 5x5: the source center is the frame center
+        let descriptor = SheetDescriptor::parse(r#"{
+            "frames": { "a": { "frame": { "x": 0, "y": 0, "w": 10, "h": 10 }, "trimmed": true,
+                "spriteSourceSize": { "x": 5, "y": 5, "w": 10, "h": 10 }, "sourceSize": { "w": 20, "h": 20 } } },
+            "meta": { "image": "a.png" }
+        }"#).unwrap();
+        assert_eq!(descriptor.frames[0].pivot, (0.5, 0.5));
+
+        let descriptor = SheetDescriptor::parse(r#"{
+            "frames": { "a": { "frame": { "x": 0, "y": 0, "w": 10, "h": 10 }, "trimmed": true,
+                "spriteSourceSize": { "x": 0, "y": 0, "w": 10, "h": 10 }, "sourceSize": { "w": 20, "h": 20 } } },
+            "meta": { "image": "a.png" }
+        }"#).unwrap();
+        assert_eq!(descriptor.frames[0].pivot, (1.0, 1.0));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_layout_error(SheetDescriptor::parse(r#"{ "image": "a.png", "frames": [ "#));
+        assert_layout_error(SheetDescriptor::parse(r#"{ "frames": [] }"#));
+        assert_layout_error(SheetDescriptor::parse(r#"{ "image": "a.png", "frames": [ { "x": -1, "y": 0, "w": 1, "h": 1 } ] }"#));
+        assert_layout_error(SheetDescriptor::parse(r#"{ "image": "a.png", "frames": [ { "x": 0, "y": 0, "w": 1 } ] }"#));
+        assert_layout_error(SheetDescriptor::parse(r#"{ "image": "a.png", "frames": [ { "x": 0, "y": 0, "w": 1, "h": 1, "slice": [ 1, 1 ] } ] }"#));
+        assert_layout_error(SheetDescriptor::parse(r#"{ "frames": { "a": { "frame": { "x": 0, "y": 0, "w": 1, "h": 2 }, "rotated": true } }, "meta": { "image": "a.png" } }"#));
+        assert_layout_error(SheetDescriptor::parse(&format!(r#"{{ "image": "a.png", "frames": {}{} }}"#, "[".repeat(100000), "]".repeat(100000))));
+    }
+}
@@ -1,77 +1,153 @@
 use prelude::*;
-use core::{self, Renderer, Layer, Context, RawFrame};
+use core::{self, Renderer, Layer, Context, RawFrame, SpriteStorage, SpriteDrawBuilder, SlicedDrawBuilder, MeshVertex, Vertex};
+use core::sheet::{SheetDescriptor, SheetFrame};
 use core::math::*;
 use Color;
 use image::{self, GenericImage};
 use regex::Regex;
+use std::io::Read;
+
+/// Maximum number of tiles per axis of a tiled slice. Slices requiring more tiles are stretched instead.
+const MAX_SLICE_TILES: f32 = 256.0;
 
 /// A sprite used for drawing on a [`Layer`](struct.Layer.html).
 ///
 /// Sprites are created from spritesheets containing one or more frames. To determine frame
 /// dimensions, [`Sprite::from_file()`](#method.from_file) expects sprite sheet file names to
-/// follow a specific pattern. (Future versions will add more configurable means to load sprites.)
+/// follow a specific pattern. Alternatively, [`Sprite::from_sheet_descriptor()`](#method.from_sheet_descriptor)
+/// loads named frames of arbitrary size from a JSON descriptor file.
 #[derive(Clone, Debug)]
 pub struct Sprite {
-    anchor  : Point2<f32>,
+    anchor  : Option<Point2<f32>>,
     data    : Arc<SpriteData>,
 }
 
 impl<'a> Sprite {
 
     /// Creates a new sprite texture. Given filename is expected to end
-    /// on _<width>x<height>x<frames>.<extension>, e.g. asteroid_64x64x24.png.
+    /// on _<width>x<height>x<frames>.<extension>, e.g. asteroid_64x64x24.png. Returns `Error::SpriteLayoutError`
+    /// if the described layout does not fit the image.
+    ///
+    /// If a text file of the same name with a `.txt` extension exists (as written by `tools/spritesheet.rs`), each of its
+    /// lines is used as the name of the corresponding frame. See [`Sprite::frame_id()`](#method.frame_id).
     pub fn from_file(context: &Context, file: &str) -> core::Result<Self> {
         let path = Path::new(file);
         let mut image = image::open(&path)?;
-        let parameters = Self::parse_parameters(image.dimensions(), path);
-        let descriptor = Self::build_raw_frames(&mut image, &parameters);
-        Result::Ok(Self::new(context, descriptor))
+        let parameters = Self::parse_parameters(image.dimensions(), path)?;
+        Self::check_parameters(image.dimensions(), &parameters)?;
+        let mut frames = Self::sheet_frames(&parameters);
+        Self::read_frame_names(&path.with_extension("txt"), &mut frames)?;
+        let storage = context.sprite_storage();
+        let descriptor = Self::build_raw_frames(context, &mut image, frames, parameters.components, storage)?;
+        Self::new(context, descriptor, storage)
+    }
+
+    /// Creates a new sprite texture from a JSON sprite sheet descriptor. The descriptor names the sheet image, relative
+    /// to the descriptor's location, and the position, size, pivot and duration of each frame. Supported are
+    /// TexturePacker and Aseprite JSON files (hash or array variants) as well as the following format, where
+    /// `name`, `pivot` (relative to the frame size, defaults to the center) and `duration` (milliseconds) are optional:
+    ///
+    /// ```text
+    /// {
+    ///     "image": "ship.png",
+    ///     "frames": [
+    ///         { "name": "idle", "x": 0, "y": 0, "w": 64, "h": 48, "pivot": [ 0.5, 1.0 ], "duration": 100 },
+    ///         { "name": "boost", "x": 64, "y": 0, "w": 64, "h": 60, "pivot": [ 0.5, 1.0 ], "duration": 100 }
+    ///     ]
+    /// }
+    /// ```
+    ///
+    /// Frames may additionally specify nine-slice border insets as `"slice": [ left, top, right, bottom ]` in pixels,
+    /// TexturePacker's `scale9Borders` are supported as well. See [`Sprite::draw_sliced()`](#method.draw_sliced).
+    ///
+    /// Returns `Error::SpriteLayoutError` if the descriptor cannot be parsed or a frame does not fit the image.
+    pub fn from_sheet_descriptor(context: &Context, file: &str) -> core::Result<Self> {
+        let path = Path::new(file);
+        let mut source = String::new();
+        File::open(&path)?.read_to_string(&mut source)?;
+        let descriptor = SheetDescriptor::parse(&source)?;
+        let image_path = path.parent().unwrap_or(Path::new("")).join(&descriptor.image);
+        let mut image = image::open(&image_path)?;
+        Self::check_frames(image.dimensions(), &descriptor.frames)?;
+        let storage = context.sprite_storage();
+        let descriptor = Self::build_raw_frames(context, &mut image, descriptor.frames, 1, storage)?;
+        Self::new(context, descriptor, storage)
     }
 
     /// Creates a new sprite texture.
     pub fn from_data(context: &Context, data: &[u8], parameters: &SpriteParameters) -> core::Result<Self> {
         let mut image = image::load_from_memory(data)?;
-        let descriptor = Self::build_raw_frames(&mut image, parameters);
-        Result::Ok(Self::new(context, descriptor))
+        Self::check_parameters(image.dimensions(), parameters)?;
+        let storage = context.sprite_storage();
+        let descriptor = Self::build_raw_frames(context, &mut image, Self::sheet_frames(parameters), parameters.components, storage)?;
+        Self::new(context, descriptor, storage)
     }
 
     /// Draws a sprite onto the given layer.
     pub fn draw<T>(self: &Self, layer: &Layer, frame_id: u32, position: T, color: Color) -> &Self where Point2: From<T> {
-        let bucket_id = self.data.bucket_id;
-        let texture_id = self.texture_id(frame_id);
-        let uv = ((0.0, 0.0), (self.data.uv_max.0, self.data.uv_max.1));
-        let dim = (self.data.width as f32, self.data.height as f32);
-        let scale = (1.0, 1.0);
-        let generation = self.data.generation.load(Ordering::Relaxed);
-        layer.add_rect(Some(generation), bucket_id, texture_id, self.data.components, uv, Point2::from(position), self.anchor, dim, color, 0.0, scale);
+        self.draw_frame(layer, frame_id, Point2::from(position), color, 0.0, (1.0, 1.0));
         self
     }
 
     /// Draws a sprite onto the given layer and applies given color, rotation and scaling.
     pub fn draw_transformed<T, U>(self: &Self, layer: &Layer, frame_id: u32, position: T, color: Color, rotation: f32, scale: U) -> &Self where Point2: From<T>, Point2: From<U> {
-        let bucket_id = self.data.bucket_id;
-        let texture_id = self.texture_id(frame_id);
-        let uv = ((0.0, 0.0), (self.data.uv_max.0, self.data.uv_max.1));
-        let dim = (self.data.width as f32, self.data.height as f32);
+        self.draw_frame(layer, frame_id, Point2::from(position), color, rotation, Point2::from(scale));
+        self
+    }
+
+    /// Returns a [sprite drawing builder](support/struct.SpriteDrawBuilder.html) for drawing given frame onto the given layer
+    /// with additional options like flipping, cropping and skewing.
+    pub fn draw_builder<'b>(self: &'b Self, layer: &'b Layer, frame_id: u32) -> SpriteDrawBuilder<'b> {
+        SpriteDrawBuilder::new(self, layer, frame_id)
+    }
+
+    /// Returns a [nine-slice drawing builder](support/struct.SlicedDrawBuilder.html) for drawing given frame onto the
+    /// given layer, stretched to fill a rectangle given as `((left, top), (width, height))` while preserving its borders.
+    pub fn draw_sliced<'b, T>(self: &'b Self, layer: &'b Layer, frame_id: u32, rect: T) -> SlicedDrawBuilder<'b> where Rect: From<T> {
+        SlicedDrawBuilder::new(self, layer, frame_id, Rect::from(rect))
+    }
+
+    /// Draws a triangle mesh textured with given frame onto the given layer, e.g. to deform the frame. Mesh texture
+    /// coordinates are relative to the frame, (0.0, 0.0) being its top left and (1.0, 1.0) its bottom right corner.
+    /// See [`Layer::draw_mesh()`](struct.Layer.html#method.draw_mesh) for a description of the indices and the z value.
+    pub fn draw_mesh<T>(self: &Self, layer: &Layer, frame_id: u32, position: T, vertices: &[MeshVertex], indices: &[u32], z: f32) -> &Self where Point2: From<T> {
+        let frame = self.frame(frame_id);
+        let texture_id = frame.texture_id.load(Ordering::Relaxed) as u32;
         let generation = self.data.generation.load(Ordering::Relaxed);
-        layer.add_rect(Some(generation), bucket_id, texture_id, self.data.components, uv, Point2::from(position), self.anchor, dim, color, rotation, Point2::from(scale));
+        let position = Point2::from(position);
+        let (uv_min, uv_max) = frame.uv;
+        layer.add_mesh(Some(generation), vertices, indices, |vertex| {
+            Vertex {
+                position    : [ position.0, position.1 ],
+                offset      : [ vertex.position.0, vertex.position.1 ],
+                rotation    : 0.0,
+                color       : vertex.color.into(),
+                bucket_id   : self.data.bucket_id as u32,
+                texture_id  : texture_id,
+                texture_uv  : [ uv_min.0 + vertex.uv.0 * (uv_max.0 - uv_min.0), uv_min.1 + vertex.uv.1 * (uv_max.1 - uv_min.1) ],
+                components  : self.data.components as u32,
+                z           : z,
+                draw_quads  : 0,
+            }
+        });
         self
     }
 
-    /// Defines the sprite origin. Defaults to (0.5, 0.5), meaning that the center of the
-    /// sprite would be drawn at the coordinates given to [`Sprite::draw()`](#method.draw). Likewise, (0.0, 0.0)
-    /// would mean that the sprite's top left corner would be drawn at the given coordinates.
+    /// Defines the sprite origin. Defaults to (0.5, 0.5) or the frame's pivot when loaded from a sheet descriptor,
+    /// meaning that the center of the sprite would be drawn at the coordinates given to [`Sprite::draw()`](#method.draw).
+    /// Likewise, (0.0, 0.0) would mean that the sprite's top left corner would be drawn at the given coordinates.
+    /// The anchor is relative to the size of the drawn frame and overrides the pivots of all frames.
     pub fn set_anchor(self: &mut Self, anchor: Point2) -> &Self {
-        self.anchor = (anchor.0 * self.data.width as f32, anchor.1 * self.data.height as f32);
+        self.anchor = Some(anchor);
         self
     }
 
-    /// Returns the width of the sprite.
+    /// Returns the width of the sprite. For sprites with frames of different sizes this is the width of the widest frame.
     pub fn width(self: &Self) -> u32 {
         self.data.width as u32
     }
 
-    /// Returns the height of the sprite.
+    /// Returns the height of the sprite. For sprites with frames of different sizes this is the height of the highest frame.
     pub fn height(self: &Self) -> u32 {
         self.data.height as u32
     }
@@ -81,19 +157,71 @@ impl<'a> Sprite {
         self.data.num_frames as u32
     }
 
+    /// Returns the number of components of the sprite.
+    pub fn num_components(self: &Self) -> u32 {
+        self.data.components as u32
+    }
+
+    /// Draws the frame with given name onto the given layer. Panics if the sprite has no frame of that name,
+    /// use [`Sprite::frame_id()`](#method.frame_id) to check first.
+    pub fn draw_named<T>(self: &Self, layer: &Layer, name: &str, position: T, color: Color) -> &Self where Point2: From<T> {
+        let frame_id = self.frame_id(name).unwrap_or_else(|| panic!("Sprite has no frame named {:?}.", name));
+        self.draw_frame(layer, frame_id, Point2::from(position), color, 0.0, (1.0, 1.0));
+        self
+    }
+
+    /// Returns the id of the first frame with given name. Frames are named by sheet descriptors or by the
+    /// frame list accompanying sheets loaded with [`Sprite::from_file()`](#method.from_file).
+    pub fn frame_id(self: &Self, name: &str) -> Option<u32> {
+        self.data.names.get(name).cloned()
+    }
+
+    /// Returns the width and height of given frame.
+    pub fn frame_dimensions(self: &Self, frame_id: u32) -> Point2<u32> {
+        let dimensions = self.frame(frame_id).dimensions;
+        (dimensions.0 as u32, dimensions.1 as u32)
+    }
+
+    /// Returns the nine-slice border insets (left, top, right, bottom) of given frame in pixels, if specified by the
+    /// sheet descriptor.
+    pub fn frame_slice(self: &Self, frame_id: u32) -> Option<(f32, f32, f32, f32)> {
+        self.frame(frame_id).slice
+    }
+
+    /// Returns the display duration of given frame in seconds, if specified by the sheet descriptor.
+    pub fn frame_duration(self: &Self, frame_id: u32) -> Option<f32> {
+        self.frame(frame_id).duration
+    }
+
     /// Returns the sprite wrapped in an std::Arc
     pub fn arc(self: Self) -> Arc<Self> {
         Arc::new(self)
     }
 
     /// Creates a sprite from given descriptor.
-    fn new(context: &Context, descriptor: SpriteRawInfo) -> Self {
+    fn new(context: &Context, descriptor: SpriteRawInfo, storage: SpriteStorage) -> core::Result<Self> {
 
-        let SpriteRawInfo { bucket_id, texture_size, frame_width, frame_height, components, raw_frames } = descriptor;
-        let num_frames = (raw_frames.len() as u32 / components) as u32;
+        let SpriteRawInfo { bucket_id, texture_size, frame_width, frame_height, components, raw_frames, frames } = descriptor;
+        let num_frames = frames.len() as u32;
 
         let mut context = context.lock();
-        let texture_id = context.store_frames(bucket_id, raw_frames);
+
+        let locations = if let SpriteStorage::Atlas { padding } = storage {
+            context.store_atlas_frames(raw_frames, components, padding)?
+        } else {
+            let texture_id = context.store_frames(bucket_id, raw_frames);
+            let size = texture_size as f32;
+            frames.iter().enumerate().map(|(frame_id, frame)| {
+                (texture_id + frame_id as u32 * components, ((0.0, 0.0), (frame.dimensions.0 as f32 / size, frame.dimensions.1 as f32 / size)))
+            }).collect()
+        };
+
+        let mut names = HashMap::new();
+        for (frame_id, frame) in frames.iter().enumerate() {
+            if let Some(ref name) = frame.name {
+                names.entry(name.clone()).or_insert(frame_id as u32);
+            }
+        }
 
         let sprite_data = Arc::new(SpriteData {
             width       : frame_width as u16,
@@ -101,45 +229,56 @@ impl<'a> Sprite {
             num_frames  : num_frames as u16,
             components  : components as u8,
             bucket_id   : bucket_id as u8,
-            texture_id  : AtomicUsize::new(texture_id as usize),
-            uv_max      : (frame_width as f32 / texture_size as f32, frame_height as f32 / texture_size as f32),
+            frames      : frames.into_iter().zip(locations.into_iter()).map(|(frame, (texture_id, uv))| SpriteFrame {
+                texture_id  : AtomicUsize::new(texture_id as usize),
+                uv          : uv,
+                dimensions  : (frame.dimensions.0 as f32, frame.dimensions.1 as f32),
+                pivot       : frame.pivot,
+                duration    : frame.duration,
+                slice       : frame.slice.map(|(left, top, right, bottom)| (left as f32, top as f32, right as f32, bottom as f32)),
+            }).collect(),
+            names       : names,
             generation  : AtomicUsize::new(context.generation()),
         });
 
         context.store_sprite(bucket_id, Arc::downgrade(&sprite_data));
 
-        Sprite {
-            anchor: (frame_width as f32 / 2.0, frame_height as f32 / 2.0),
+        Ok(Sprite {
+            anchor: None,
             data: sprite_data,
-        }
+        })
     }
 
     /// Builds a sprite descriptor containing sprite dimensions and raw frames.
-    fn build_raw_frames(image: &mut image::DynamicImage, sprite_parameters: &SpriteParameters) -> SpriteRawInfo {
+    fn build_raw_frames(context: &Context, image: &mut image::DynamicImage, frames: Vec<SheetFrame>, components: u32, storage: SpriteStorage) -> core::Result<SpriteRawInfo> {
 
-        let SpriteParameters { dimensions: (frame_width, frame_height), num_frames, components, .. } = *sprite_parameters;
-        let (bucket_id, texture_size) = Renderer::bucket_info(frame_width, frame_height);
-        let num_frames = num_frames.0 * num_frames.1;
+        let frame_width = frames.iter().map(|frame| frame.dimensions.0).max().unwrap_or(0);
+        let frame_height = frames.iter().map(|frame| frame.dimensions.1).max().unwrap_or(0);
+        let (bucket_id, texture_size) = if storage == SpriteStorage::Buckets {
+            Renderer::bucket_info(frame_width, frame_height, context.lock().max_texture_size())?
+        } else {
+            (core::ATLAS_BUCKET, 0)
+        };
         let mut raw_frames = Vec::new();
 
-        for frame_id in 0..num_frames {
+        for frame in frames.iter() {
             for component in 0..components {
-                raw_frames.push(Self::build_raw_frame(image, sprite_parameters, frame_id, component, texture_size));
+                raw_frames.push(Self::build_raw_frame(image, frame, component, texture_size));
             }
         }
 
-        SpriteRawInfo { bucket_id, texture_size, frame_width, frame_height, components, raw_frames }
+        Ok(SpriteRawInfo { bucket_id, texture_size, frame_width, frame_height, components, raw_frames, frames })
     }
 
     /// Constructs a single RawFrame for a frame of a spritesheet
-    /// If neccessary, pads the image up to the next power of two
-    fn build_raw_frame(image: &mut image::DynamicImage, sprite_parameters: &SpriteParameters, frame_id: u32, component: u32, pad_size: u32) -> RawFrame {
+    /// If neccessary, pads the image up to the next power of two. A pad_size of 0 disables padding.
+    fn build_raw_frame(image: &mut image::DynamicImage, frame: &SheetFrame, component: u32, pad_size: u32) -> RawFrame {
 
-        let SpriteParameters { dimensions: (frame_width, frame_height), .. } = *sprite_parameters;
-        let (x, y) = Self::get_frame_coordinates(sprite_parameters, frame_id, component);
+        let (frame_width, frame_height) = frame.dimensions;
+        let (x, y) = frame.positions[component as usize];
         let subimage = image.crop(x, y, frame_width, frame_height);
 
-        if frame_width != pad_size || frame_height != pad_size {
+        if pad_size != 0 && (frame_width != pad_size || frame_height != pad_size) {
 
             // pad image if it doesn't match an available texture array size
             let mut dest = image::DynamicImage::new_rgba8(pad_size, pad_size);
@@ -163,6 +302,35 @@ impl<'a> Sprite {
         }
     }
 
+    /// Names frames after the lines of given text file, if it exists.
+    fn read_frame_names(path: &Path, frames: &mut [SheetFrame]) -> core::Result<()> {
+        if !path.is_file() {
+            return Ok(());
+        }
+        let mut source = String::new();
+        File::open(path)?.read_to_string(&mut source)?;
+        for (frame, line) in frames.iter_mut().zip(source.lines()) {
+            let name = line.trim();
+            if name.len() > 0 {
+                frame.name = Some(name.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Lists the frames described by given sprite parameters.
+    fn sheet_frames(sprite_parameters: &SpriteParameters) -> Vec<SheetFrame> {
+        let SpriteParameters { dimensions, num_frames, components, .. } = *sprite_parameters;
+        (0..num_frames.0 * num_frames.1).map(|frame_id| SheetFrame {
+            positions   : (0..components).map(|component| Self::get_frame_coordinates(sprite_parameters, frame_id, component)).collect(),
+            dimensions  : dimensions,
+            pivot       : (0.5, 0.5),
+            duration    : None,
+            name        : None,
+            slice       : None,
+        }).collect()
+    }
+
     /// Computes top/left frame coordinates for the given frame_id/component in a sprite-sheet
     fn get_frame_coordinates(sprite_parameters: &SpriteParameters, frame_id: u32, component: u32) -> (u32, u32) {
 
@@ -185,7 +353,7 @@ impl<'a> Sprite {
     }
 
     /// Parses sprite-sheet filename for dimensions and frame count
-    fn parse_parameters(dimensions: (u32, u32), path: &Path) -> SpriteParameters {
+    fn parse_parameters(dimensions: (u32, u32), path: &Path) -> core::Result<SpriteParameters> {
 
         // e.g. mysprite_16x16x30.png (16x16, 30 frames)
         // mysprite_16x16x30x2.png (16x16, 30 frames, 2 components)
@@ -193,55 +361,283 @@ impl<'a> Sprite {
         // mysprite_16x16+1.png (16x16, inner margin of 1 px, all frames horizontally ordered)
         lazy_static! { static ref MATCHER: Regex = Regex::new(r"_(\d+)x(\d+)(?:x(\d+)(?:x(\d+))?)?(?:\+(\d+))?\.").unwrap(); }
 
-        let filename = path.file_name().unwrap().to_str().unwrap();
+        let filename = path.file_name().and_then(|name| name.to_str()).ok_or_else(|| layout_error(format!("Invalid sprite sheet filename {:?}", path)))?;
         let captures = MATCHER.captures(filename);
 
         match captures {
             Some(captures) => {
-                let frame_width = captures.get(1).unwrap().as_str().parse::<u32>().unwrap();
-                let frame_height = captures.get(2).unwrap().as_str().parse::<u32>().unwrap();
-                let frame_count = captures.get(3).map_or("0", |m| m.as_str()).parse::<u32>().unwrap();
-                let frame_channels = captures.get(4).map_or("1", |m| m.as_str()).parse::<u32>().unwrap();
-                let inner_margin = captures.get(5).map_or("0", |m| m.as_str()).parse::<u32>().unwrap();
-                let frame_layout = if frame_height == dimensions.1 || frame_count == 0 { SpriteLayout::HORIZONTAL } else { SpriteLayout::VERTICAL };
+                let parse = |index: usize, default: &'static str| {
+                    let value = captures.get(index).map_or(default, |m| m.as_str());
+                    value.parse::<u32>().map_err(|_| layout_error(format!("Invalid number {} in sprite sheet filename {}", value, filename)))
+                };
+                let frame_width = parse(1, "0")?;
+                let frame_height = parse(2, "0")?;
+                let frame_count = parse(3, "0")?;
+                let frame_channels = parse(4, "1")?;
+                let inner_margin = parse(5, "0")?;
+
+                if frame_width == 0 || frame_height == 0 {
+                    return Err(layout_error(format!("Sprite sheet {} has frames of zero size", filename)));
+                }
+
+                // a horizontal sheet is exactly one frame (with all its components) high
+                let stacked_height = frame_height as u64 * frame_channels as u64 + inner_margin as u64 * (frame_channels as u64).saturating_sub(1);
+                let frame_layout = if stacked_height == dimensions.1 as u64 || frame_count == 0 { SpriteLayout::HORIZONTAL } else { SpriteLayout::VERTICAL };
 
                 // calculate frame counts if not provided
                 let num_frames = if frame_count == 0 {
-                    let num_x = dimensions.0 as f32 / (frame_width + inner_margin) as f32 + (inner_margin as f32 / (frame_width + inner_margin) as f32);
-                    let num_y = dimensions.1 as f32 / (frame_height + inner_margin) as f32 + (inner_margin as f32 / (frame_height + inner_margin) as f32);
-                    assert!(num_x.fract() <= f32::EPSILON, "bad sprite geometry: slicing image horizontally into frames resulted in non-integer frame-count");
-                    assert!(num_y.fract() <= f32::EPSILON, "bad sprite geometry: slicing image vertically into frames resulted in non-integer frame-count");
-                    (num_x as u32, num_y as u32)
+                    let (sheet_x, sheet_y) = (dimensions.0 as u64 + inner_margin as u64, dimensions.1 as u64 + inner_margin as u64);
+                    let (cell_x, cell_y) = (frame_width as u64 + inner_margin as u64, frame_height as u64 + inner_margin as u64);
+                    if sheet_x % cell_x != 0 {
+                        return Err(layout_error(format!("Bad sprite geometry: slicing {} horizontally into frames resulted in non-integer frame-count", filename)));
+                    }
+                    if sheet_y % cell_y != 0 {
+                        return Err(layout_error(format!("Bad sprite geometry: slicing {} vertically into frames resulted in non-integer frame-count", filename)));
+                    }
+                    ((sheet_x / cell_x) as u32, (sheet_y / cell_y) as u32)
                 } else if frame_layout == SpriteLayout::HORIZONTAL {
                     (frame_count, 1)
                 } else {
                     (1, frame_count)
                 };
 
-                SpriteParameters {
+                Ok(SpriteParameters {
                     dimensions  : (frame_width, frame_height),
                     inner_margin: inner_margin,
                     num_frames  : num_frames,
                     components  : frame_channels,
                     layout      : frame_layout
-                }
+                })
             }
-            None => SpriteParameters {
+            None => Ok(SpriteParameters {
                 dimensions  : dimensions,
                 inner_margin: 0,
                 num_frames  : (1, 1),
                 components  : 1,
                 layout      : SpriteLayout::HORIZONTAL
+            })
+        }
+    }
+
+    /// Verifies that the sprite parameters describe at least one frame and that all frames are contained within
+    /// a sheet of the given dimensions.
+    fn check_parameters(dimensions: (u32, u32), sprite_parameters: &SpriteParameters) -> core::Result<()> {
+
+        let SpriteParameters { dimensions: (frame_width, frame_height), inner_margin, num_frames, components, layout } = *sprite_parameters;
+
+        if frame_width == 0 || frame_height == 0 {
+            return Err(layout_error(format!("Sprite frames of {}x{} pixels are empty", frame_width, frame_height)));
+        }
+        if num_frames.0 == 0 || num_frames.1 == 0 || components == 0 {
+            return Err(layout_error(format!("Sprite sheet needs at least one frame and component, got {}x{} frames with {} components", num_frames.0, num_frames.1, components)));
+        }
+
+        // number of frame cells along each axis. components are stacked orthogonally to the frames.
+        let (cells_x, cells_y) = if layout == SpriteLayout::HORIZONTAL {
+            (num_frames.0 as u64, num_frames.1 as u64 + components as u64 - 1)
+        } else {
+            (num_frames.0 as u64 + components as u64 - 1, num_frames.1 as u64)
+        };
+        let required_width = cells_x * frame_width as u64 + (cells_x - 1) * inner_margin as u64;
+        let required_height = cells_y * frame_height as u64 + (cells_y - 1) * inner_margin as u64;
+
+        if required_width > dimensions.0 as u64 || required_height > dimensions.1 as u64 {
+            return Err(layout_error(format!(
+                "Sprite sheet of {}x{} pixels is too small for {} frames of {}x{} pixels with {} components (requires {}x{} pixels)",
+                dimensions.0, dimensions.1, num_frames.0 * num_frames.1, frame_width, frame_height, components, required_width, required_height
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Verifies that a sheet descriptor lists at least one frame and that all frames are non-empty and contained
+    /// within a sheet of the given dimensions.
+    fn check_frames(dimensions: (u32, u32), frames: &[SheetFrame]) -> core::Result<()> {
+
+        if frames.len() == 0 {
+            return Err(layout_error("Sprite sheet descriptor lists no frames".to_string()));
+        }
+        if frames.len() > u16::max_value() as usize {
+            return Err(layout_error(format!("Sprite sheet descriptor lists {} frames, at most {} are supported", frames.len(), u16::max_value())));
+        }
+
+        for (frame_id, frame) in frames.iter().enumerate() {
+            let (frame_width, frame_height) = frame.dimensions;
+            if frame_width == 0 || frame_height == 0 {
+                return Err(layout_error(format!("Sprite frame {} of {}x{} pixels is empty", frame_id, frame_width, frame_height)));
+            }
+            for &(x, y) in frame.positions.iter() {
+                if x as u64 + frame_width as u64 > dimensions.0 as u64 || y as u64 + frame_height as u64 > dimensions.1 as u64 {
+                    return Err(layout_error(format!(
+                        "Sprite frame {} at {}x{} with {}x{} pixels exceeds sprite sheet of {}x{} pixels",
+                        frame_id, x, y, frame_width, frame_height, dimensions.0, dimensions.1
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds given frame to the layer.
+    fn draw_frame(self: &Self, layer: &Layer, frame_id: u32, position: Point2, color: Color, rotation: f32, scale: Point2) {
+        layer.add_quad(Some(self.generation()), self.frame_vertices(frame_id, position, color, rotation, scale));
+    }
+
+    /// Returns the vertices of given frame.
+    pub(crate) fn frame_vertices(self: &Self, frame_id: u32, position: Point2, color: Color, rotation: f32, scale: Point2) -> [Vertex; 4] {
+        let frame = self.frame(frame_id);
+        let texture_id = frame.texture_id.load(Ordering::Relaxed) as u32;
+        let anchor = self.anchor.unwrap_or(frame.pivot);
+        let anchor = (anchor.0 * frame.dimensions.0, anchor.1 * frame.dimensions.1);
+        Layer::rect_vertices(self.data.bucket_id, texture_id, self.data.components, frame.uv, position, anchor, frame.dimensions, color, rotation, scale)
+    }
+
+    /// Returns the content generation the sprite was created in.
+    pub(crate) fn generation(self: &Self) -> usize {
+        self.data.generation.load(Ordering::Relaxed)
+    }
+
+    /// Adds a frame configured by given sprite drawing builder to the layer.
+    pub(crate) fn draw_from_builder(self: &Self, layer: &Layer, builder: &SpriteDrawBuilder) {
+
+        let frame = self.frame(builder.frame_id);
+        let (texture_id, components) = match builder.component {
+            Some(component) => (frame.texture_id.load(Ordering::Relaxed) as u32 + component, 1),
+            None => (frame.texture_id.load(Ordering::Relaxed) as u32, self.data.components as u32),
+        };
+        let generation = self.data.generation.load(Ordering::Relaxed);
+        let anchor = builder.anchor.or(self.anchor).unwrap_or(frame.pivot);
+        let dim = frame.dimensions;
+        let (uv_min, uv_max) = frame.uv;
+        let flip = (if builder.flip.0 { -1.0 } else { 1.0 }, if builder.flip.1 { -1.0 } else { 1.0 });
+        // corner colors are given for the sprite as it appears on the layer
+        let color_index = |x_index: usize, y_index: usize| {
+            (if builder.flip.1 { 1 - y_index } else { y_index }) * 2 + if builder.flip.0 { 1 - x_index } else { x_index }
+        };
+
+        // corners of the visible portion, relative to the frame
+        let ((crop_x, crop_y), (crop_width, crop_height)) = builder.crop;
+        let clamp = |value: f32| value.max(0.0).min(1.0);
+        let (x0, y0, x1, y1) = (clamp(crop_x), clamp(crop_y), clamp(crop_x + crop_width), clamp(crop_y + crop_height));
+
+        let corner = |x: f32, y: f32, x_index: usize, y_index: usize| {
+            let offset = ((x - anchor.0) * dim.0 * builder.scale.0 * flip.0, (y - anchor.1) * dim.1 * builder.scale.1 * flip.1);
+            Vertex {
+                position    : [ builder.position.0, builder.position.1 ],
+                offset      : [ offset.0 + builder.skew.0 * offset.1, offset.1 + builder.skew.1 * offset.0 ],
+                rotation    : builder.rotation,
+                color       : builder.colors[color_index(x_index, y_index)].into(),
+                bucket_id   : self.data.bucket_id as u32,
+                texture_id  : texture_id,
+                texture_uv  : [ uv_min.0 + x * (uv_max.0 - uv_min.0), uv_min.1 + y * (uv_max.1 - uv_min.1) ],
+                components  : components,
+                z           : builder.z,
+                draw_quads  : 0,
+            }
+        };
+
+        layer.add_quad(Some(generation), [ corner(x0, y0, 0, 0), corner(x1, y0, 1, 0), corner(x0, y1, 0, 1), corner(x1, y1, 1, 1) ]);
+    }
+
+    /// Adds a frame configured by given nine-slice drawing builder to the layer.
+    pub(crate) fn draw_from_sliced_builder(self: &Self, layer: &Layer, builder: &SlicedDrawBuilder) {
+
+        let frame = self.frame(builder.frame_id);
+        let texture_id = frame.texture_id.load(Ordering::Relaxed) as u32;
+        let generation = self.data.generation.load(Ordering::Relaxed);
+        let dim = frame.dimensions;
+        let (uv_min, uv_max) = frame.uv;
+        let ((x, y), (width, height)) = builder.rect;
+        let center = (x + width / 2.0, y + height / 2.0);
+
+        // clamp insets to the frame, then scale target borders down if the rectangle is too small
+        let (left, top, right, bottom) = builder.insets.or(frame.slice).unwrap_or((0.0, 0.0, 0.0, 0.0));
+        let (left, top) = (left.max(0.0).min(dim.0), top.max(0.0).min(dim.1));
+        let (right, bottom) = (right.max(0.0).min(dim.0 - left), bottom.max(0.0).min(dim.1 - top));
+        let fit = |start: f32, end: f32, size: f32| {
+            if start + end > size {
+                let factor = size.max(0.0) / (start + end);
+                (start * factor, end * factor)
+            } else {
+                (start, end)
+            }
+        };
+        let (target_left, target_right) = fit(left, right, width);
+        let (target_top, target_bottom) = fit(top, bottom, height);
+
+        // slice boundaries within the frame and the target rectangle
+        let source_x = [ 0.0, left, dim.0 - right, dim.0 ];
+        let source_y = [ 0.0, top, dim.1 - bottom, dim.1 ];
+        let target_x = [ x, x + target_left, x + width - target_right, x + width ];
+        let target_y = [ y, y + target_top, y + height - target_bottom, y + height ];
+
+        // maps a part of the frame (in pixels) to a part of the target rectangle, all quads share the rectangle's center as origin
+        let part = |source: Rect, target: Rect| {
+            let ((source_x, source_y), (source_width, source_height)) = source;
+            let ((target_x, target_y), (target_width, target_height)) = target;
+            if target_width <= 0.0 || target_height <= 0.0 {
+                return None;
+            }
+            let uv = (
+                (uv_min.0 + source_x / dim.0 * (uv_max.0 - uv_min.0), uv_min.1 + source_y / dim.1 * (uv_max.1 - uv_min.1)),
+                (uv_min.0 + (source_x + source_width) / dim.0 * (uv_max.0 - uv_min.0), uv_min.1 + (source_y + source_height) / dim.1 * (uv_max.1 - uv_min.1)),
+            );
+            let anchor = (center.0 - target_x, center.1 - target_y);
+            let mut vertices = Layer::rect_vertices(self.data.bucket_id, texture_id, self.data.components, uv, center, anchor, (target_width, target_height), builder.color, 0.0, (1.0, 1.0));
+            for vertex in vertices.iter_mut() {
+                vertex.z = builder.z;
+            }
+            Some(vertices)
+        };
+
+        // splits a stretched range into tiles of the source size (at least one pixel), the last tile is cropped
+        let tiles = |source_size: f32, target_start: f32, target_size: f32, tile: bool| -> Vec<(f32, f32, f32)> {
+            let tile_size = source_size.max(1.0);
+            let count = target_size / tile_size;
+            if !tile || source_size <= 0.0 || !count.is_finite() || count > MAX_SLICE_TILES {
+                return vec![ (target_start, target_size, 1.0) ];
+            }
+            let mut result = Vec::new();
+            let mut position = 0.0;
+            while position < target_size {
+                let size = tile_size.min(target_size - position);
+                result.push((target_start + position, size, size / tile_size));
+                position += tile_size;
+            }
+            result
+        };
+
+        let mut quads = Vec::new();
+
+        for row in 0..3 {
+            for column in 0..3 {
+                let (source_width, source_height) = (source_x[column + 1] - source_x[column], source_y[row + 1] - source_y[row]);
+                let (target_width, target_height) = (target_x[column + 1] - target_x[column], target_y[row + 1] - target_y[row]);
+                let columns = tiles(source_width, target_x[column], target_width, builder.tile && column == 1);
+                for (tile_y, tile_height, portion_y) in tiles(source_height, target_y[row], target_height, builder.tile && row == 1) {
+                    for &(tile_x, tile_width, portion_x) in columns.iter() {
+                        quads.extend(part(((source_x[column], source_y[row]), (source_width * portion_x, source_height * portion_y)), ((tile_x, tile_y), (tile_width, tile_height))));
+                    }
+                }
             }
         }
+
+        layer.add_quads(Some(generation), &quads);
     }
 
-    /// Returns the texture id for given frame
-    fn texture_id(self: &Self, frame_id: u32) -> u32 {
-        self.data.texture_id.load(Ordering::Relaxed) as u32 + (frame_id % self.data.num_frames as u32) * (self.data.components as u32)
+    /// Returns the location and metadata of given frame
+    fn frame(self: &Self, frame_id: u32) -> &SpriteFrame {
+        &self.data.frames[(frame_id % self.data.num_frames as u32) as usize]
     }
 }
 
+/// Creates a sprite layout error with given message.
+fn layout_error(message: String) -> core::Error {
+    core::Error::SpriteLayoutError(message)
+}
+
 /// Internal sprite data. (Multiple) sprites can hold a reference to this.
 #[derive(Debug)]
 pub struct SpriteData {
@@ -250,9 +646,26 @@ pub struct SpriteData {
     pub num_frames  : u16,
     pub components  : u8,
     bucket_id       : u8,
-    pub texture_id  : AtomicUsize,
+    pub frames      : Vec<SpriteFrame>,
+    names           : HashMap<String, u32>,
     pub generation  : AtomicUsize,
-    uv_max          : Point2<f32>,
+}
+
+/// Location of a single sprite frame within the context's textures.
+#[derive(Debug)]
+pub struct SpriteFrame {
+    /// First texture array layer of the frame. Components are stored in consecutive layers.
+    pub texture_id  : AtomicUsize,
+    /// Texture coordinates of the frame within its layer.
+    pub uv          : Rect,
+    /// Width and height of the frame in pixels.
+    pub dimensions  : Point2<f32>,
+    /// Default anchor, relative to the frame dimensions.
+    pub pivot       : Point2<f32>,
+    /// Display duration in seconds.
+    pub duration    : Option<f32>,
+    /// Nine-slice border insets (left, top, right, bottom) in pixels.
+    pub slice       : Option<(f32, f32, f32, f32)>,
 }
 
 /// Sprite parameter layout type. Sprites are arranged either horizontally or
@@ -286,4 +699,100 @@ struct SpriteRawInfo {
     frame_height    : u32,
     components      : u32,
     raw_frames      : Vec<RawFrame>,
+    frames          : Vec<SheetFrame>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(dimensions: (u32, u32), filename: &str) -> core::Result<SpriteParameters> {
+        let parameters = Sprite::parse_parameters(dimensions, Path::new(filename))?;
+        Sprite::check_parameters(dimensions, &parameters)?;
+        Ok(parameters)
+    }
+
+    fn assert_layout_error(result: core::Result<SpriteParameters>) {
+        match result {
+            Err(core::Error::SpriteLayoutError(_)) => { }
+            other => panic!("expected SpriteLayoutError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn frames() {
+        let parameters = parse((480, 16), "mysprite_16x16x30.png").unwrap();
+        assert_eq!(parameters.dimensions, (16, 16));
+        assert_eq!(parameters.num_frames, (30, 1));
+        assert_eq!(parameters.components, 1);
+        assert_eq!(parameters.inner_margin, 0);
+        assert_eq!(parameters.layout, SpriteLayout::HORIZONTAL);
+    }
+
+    #[test]
+    fn vertical_frames() {
+        let parameters = parse((16, 480), "mysprite_16x16x30.png").unwrap();
+        assert_eq!(parameters.num_frames, (1, 30));
+        assert_eq!(parameters.layout, SpriteLayout::VERTICAL);
+    }
+
+    #[test]
+    fn components() {
+        let parameters = parse((480, 32), "mysprite_16x16x30x2.png").unwrap();
+        assert_eq!(parameters.num_frames, (30, 1));
+        assert_eq!(parameters.components, 2);
+        assert_eq!(parameters.layout, SpriteLayout::HORIZONTAL);
+    }
+
+    #[test]
+    fn frames_with_margin() {
+        let parameters = parse((509, 16), "mysprite_16x16x30+1.png").unwrap();
+        assert_eq!(parameters.num_frames, (30, 1));
+        assert_eq!(parameters.inner_margin, 1);
+    }
+
+    #[test]
+    fn margin_only() {
+        let parameters = parse((50, 16), "mysprite_16x16+1.png").unwrap();
+        assert_eq!(parameters.dimensions, (16, 16));
+        assert_eq!(parameters.num_frames, (3, 1));
+        assert_eq!(parameters.inner_margin, 1);
+        assert_eq!(parameters.layout, SpriteLayout::HORIZONTAL);
+    }
+
+    #[test]
+    fn no_pattern() {
+        let parameters = parse((20, 10), "background.png").unwrap();
+        assert_eq!(parameters.dimensions, (20, 10));
+        assert_eq!(parameters.num_frames, (1, 1));
+    }
+
+    #[test]
+    fn non_integer_frame_count() {
+        assert_layout_error(parse((40, 16), "mysprite_16x16.png"));
+        assert_layout_error(parse((48, 20), "mysprite_16x16.png"));
+    }
+
+    #[test]
+    fn zero_size_frames() {
+        assert_layout_error(parse((64, 16), "mysprite_0x16x4.png"));
+        assert_layout_error(parse((64, 16), "mysprite_16x0.png"));
+        assert_layout_error(parse((8, 8), "mysprite_16x16.png"));
+    }
+
+    #[test]
+    fn too_many_components() {
+        assert_layout_error(parse((64, 32), "mysprite_16x16x4x3.png"));
+    }
+
+    #[test]
+    fn frames_off_edge() {
+        assert_layout_error(parse((64, 16), "mysprite_16x16x8.png"));
+        assert_layout_error(parse((509, 16), "mysprite_16x16x31+1.png"));
+    }
+
+    #[test]
+    fn number_overflow() {
+        assert_layout_error(parse((64, 16), "mysprite_99999999999x16.png"));
+    }
 }
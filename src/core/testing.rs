@@ -0,0 +1,128 @@
+use prelude::*;
+use core::{self, Renderer, Texture, TextureFormat, Color, Point2};
+use image;
+use std::env;
+
+/// Name of the environment variable that, when set, causes [`Snapshot::compare()`](struct.Snapshot.html#method.compare)
+/// to store the rendered image as the new reference image instead of comparing against it.
+pub const UPDATE_SNAPSHOTS_VAR: &'static str = "RADIANT_UPDATE_SNAPSHOTS";
+
+/// A rendered image that can be compared against a stored reference ("golden") image.
+///
+/// When a comparison fails, the rendered image, a copy of the reference image and an image
+/// highlighting the differences are written next to the reference file, e.g. `sprite.png` results in
+/// `sprite.actual.png`, `sprite.expected.png` and `sprite.diff.png`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use radiant_rs::*;
+/// # use radiant_rs::testing::Snapshot;
+/// # let display = Display::builder().hidden().build().unwrap();
+/// # let renderer = Renderer::new(&display).unwrap();
+/// # let layer = Layer::new((64., 64.));
+/// let snapshot = Snapshot::render(&renderer, (64, 64), || {
+///     renderer.clear(Color::BLACK);
+///     renderer.draw_layer(&layer, 0);
+/// });
+///
+/// snapshot.assert_matches("tests/snapshots/layer.png", 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    image: image::RgbaImage,
+}
+
+impl Snapshot {
+
+    /// Renders draws issued within `draw_func()` to a new, transparent texture of given dimensions and
+    /// returns the result.
+    pub fn render<T, F>(renderer: &Renderer, dimensions: T, draw_func: F) -> Self where Point2<u32>: From<T>, F: FnMut() {
+        let texture = Texture::builder(&renderer.context).dimensions(dimensions).format(TextureFormat::U8U8U8U8).build().unwrap();
+        texture.clear(Color::TRANSPARENT);
+        renderer.render_to(&texture, draw_func);
+        Snapshot { image: texture.read_pixels() }
+    }
+
+    /// Creates a snapshot from an existing image.
+    pub fn from_image(image: image::RgbaImage) -> Self {
+        Snapshot { image: image }
+    }
+
+    /// Returns the rendered image.
+    pub fn image(self: &Self) -> &image::RgbaImage {
+        &self.image
+    }
+
+    /// Writes the rendered image to given file.
+    pub fn save(self: &Self, file: &str) -> core::Result<()> {
+        self.image.save(Path::new(file))?;
+        Ok(())
+    }
+
+    /// Compares the rendered image against the reference image stored in given file and returns the number of
+    /// pixels where any channel differs by more than `tolerance`. If the images differ, the rendered, reference and
+    /// difference images are written next to the reference file.
+    pub fn compare(self: &Self, file: &str, tolerance: u8) -> core::Result<u32> {
+
+        let path = Path::new(file);
+
+        if env::var_os(UPDATE_SNAPSHOTS_VAR).is_some() {
+            self.image.save(path)?;
+            return Ok(0);
+        }
+
+        let expected = match image::open(path) {
+            Ok(expected) => expected.to_rgba(),
+            Err(error) => {
+                self.image.save(path.with_extension("actual.png"))?;
+                return Err(error.into());
+            }
+        };
+
+        if expected.dimensions() != self.image.dimensions() {
+            self.image.save(path.with_extension("actual.png"))?;
+            return Err(core::Error::ImageError(format!("Snapshot dimensions {:?} differ from reference dimensions {:?}", self.image.dimensions(), expected.dimensions())));
+        }
+
+        let mut diff = image::RgbaImage::new(expected.width(), expected.height());
+        let mut mismatches = 0;
+
+        for (x, y, actual) in self.image.enumerate_pixels() {
+            let reference = expected.get_pixel(x, y);
+            let difference = (0..4).map(|c| (actual.data[c] as i16 - reference.data[c] as i16).abs() as u8).max().unwrap();
+            if difference > tolerance {
+                mismatches += 1;
+                diff.put_pixel(x, y, image::Rgba([ 255, 0, 0, 255 ]));
+            } else {
+                // dimmed reference for orientation
+                let luma = ((reference.data[0] as u32 + reference.data[1] as u32 + reference.data[2] as u32) / 12) as u8;
+                diff.put_pixel(x, y, image::Rgba([ luma, luma, luma, 255 ]));
+            }
+        }
+
+        if mismatches > 0 {
+            self.image.save(path.with_extension("actual.png"))?;
+            expected.save(path.with_extension("expected.png"))?;
+            diff.save(path.with_extension("diff.png"))?;
+        }
+
+        Ok(mismatches)
+    }
+
+    /// Compares the rendered image against the reference image stored in given file and panics if
+    /// any pixel differs by more than `tolerance` in any channel. See [`compare()`](#method.compare).
+    pub fn assert_matches(self: &Self, file: &str, tolerance: u8) {
+        match self.compare(file, tolerance) {
+            Ok(0) => { }
+            Ok(mismatches) => {
+                let path = Path::new(file);
+                panic!("Snapshot differs from {} in {} pixels. See {} and {}.", file, mismatches,
+                    path.with_extension("actual.png").display(), path.with_extension("diff.png").display());
+            }
+            Err(error) => {
+                panic!("Snapshot could not be compared to {}: {:?}", file, error);
+            }
+        }
+    }
+}
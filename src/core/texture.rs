@@ -80,6 +80,21 @@ impl Texture {
     pub fn dimensions(self: &Self) -> Point2<u32> {
         self.dimensions
     }
+    /// Reads the contents of the texture back from the GPU. The first row of the returned image is the top of the texture.
+    ///
+    /// Values are converted to 8 bits per channel, clamping formats with a larger range.
+    pub fn read_pixels(self: &Self) -> image::RgbaImage {
+        self.handle.read(((0, 0), self.dimensions))
+    }
+    /// Reads the given rectangle of the texture back from the GPU. See [`read_pixels()`](#method.read_pixels).
+    ///
+    /// This uses integral pixel coordinates (top/left = 0/0). The rectangle must be entirely contained within the texture.
+    pub fn read_rect<T>(self: &Self, rect: T) -> image::RgbaImage where core::Rect<u32>: From<T> {
+        let rect: core::Rect<u32> = rect.into();
+        let ((left, top), (width, height)) = rect;
+        assert!(left + width <= self.dimensions.0 && top + height <= self.dimensions.1, "Rectangle exceeds texture dimensions.");
+        self.handle.read(rect)
+    }
     /// Creates a new texture from given TextureBuilder.
     pub(crate) fn from_builder(mut builder: TextureBuilder) -> core::Result<Self> {
         let mut context = builder.context.lock();
@@ -0,0 +1,378 @@
+use prelude::*;
+use core::{self, blendmodes, BlendMode, Color, Layer, Sprite};
+use core::math::*;
+
+/// Number of tiles along each side of a chunk.
+const CHUNK_SIZE: u32 = 16;
+
+/// Arrangement of the cells of a [`TileMap`](struct.TileMap.html).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TileOrientation {
+    /// Rectangular cells. Cell (0, 0) is the top left cell.
+    Orthogonal,
+    /// Diamond shaped cells. Columns run down and to the right, rows down and to the left, cell (0, 0) is the top
+    /// cell and is horizontally centered on 0.0.
+    Isometric,
+    /// Pointy-topped hexagonal cells. Odd rows are shifted right by half a tile, consecutive rows overlap by a quarter
+    /// of the tile height.
+    Hexagonal,
+}
+
+/// A grid of tiles drawn from the frames of a single sprite.
+///
+/// Tile maps are divided into chunks of 16x16 tiles. Each chunk that contains tiles keeps its geometry on a
+/// [`Layer`](struct.Layer.html) of its own that is only rebuilt after one of its tiles changed, and
+/// [`Renderer::draw_tilemap()`](struct.Renderer.html#method.draw_tilemap) only renders chunks that intersect the
+/// view. Like layers, tile maps are created with a view matrix that maps the given dimensions to the entirety of the
+/// drawing target.
+///
+/// Each tile is drawn with the sprite's anchor at the center of its cell. Within a chunk, tiles are drawn row by row
+/// (isometric: diagonal by diagonal) from the top. Tiles that are larger than their cell may be overlapped by tiles
+/// of a chunk drawn later.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use radiant_rs::*;
+/// # let display = Display::builder().hidden().build().unwrap();
+/// # let renderer = Renderer::new(&display).unwrap();
+/// # let sprite = Sprite::from_file(display.context(), "examples/res/sprites/ball_v2_32x32x18.jpg").unwrap();
+/// let mut map = TileMap::new(&sprite, (100, 100), (640., 480.));
+/// map.set_tile(3, 4, Some(0));
+/// map.set_tile(4, 4, Some(1));
+///
+/// // once per frame
+/// renderer.draw_tilemap(&map, 0);
+/// ```
+#[derive(Debug)]
+pub struct TileMap {
+    sprite          : Sprite,
+    dimensions      : Point2,
+    size            : Point2<u32>,
+    tile_size       : Point2,
+    orientation     : TileOrientation,
+    tiles           : Vec<Option<u32>>,
+    chunks          : Vec<Option<TileChunk>>,
+    view_matrix     : Mutex<Mat4Stack<f32>>,
+    blend           : Mutex<BlendMode>,
+    color           : Mutex<Color>,
+}
+
+/// A chunk of a tile map and its geometry.
+#[derive(Debug)]
+struct TileChunk {
+    layer   : Layer,
+    dirty   : AtomicBool,
+}
+
+impl TileMap {
+
+    /// Creates a new, empty tile map of given size in tiles with orthogonal orientation. The tile size defaults to
+    /// the sprite's dimensions. Dimensions are used to create the view matrix, see [`Layer::new()`](struct.Layer.html#method.new).
+    pub fn new<T>(sprite: &Sprite, size: Point2<u32>, dimensions: T) -> Self where Point2: From<T> {
+        let dimensions = Point2::from(dimensions);
+        let num_chunks = Self::num_chunks(size);
+        TileMap {
+            sprite          : sprite.clone(),
+            dimensions      : dimensions,
+            size            : size,
+            tile_size       : (sprite.width() as f32, sprite.height() as f32),
+            orientation     : TileOrientation::Orthogonal,
+            tiles           : vec![ None; (size.0 * size.1) as usize ],
+            chunks          : (0..num_chunks.0 * num_chunks.1).map(|_| None).collect(),
+            view_matrix     : Mutex::new(Mat4::viewport(dimensions.0, dimensions.1).into()),
+            blend           : Mutex::new(blendmodes::ALPHA),
+            color           : Mutex::new(Color::WHITE),
+        }
+    }
+
+    /// Returns the width and height of the map in tiles.
+    pub fn size(self: &Self) -> Point2<u32> {
+        self.size
+    }
+
+    /// Sets the orientation of the map.
+    pub fn set_orientation(self: &mut Self, orientation: TileOrientation) -> &mut Self {
+        self.orientation = orientation;
+        self.invalidate();
+        self
+    }
+
+    /// Returns the orientation of the map.
+    pub fn orientation(self: &Self) -> TileOrientation {
+        self.orientation
+    }
+
+    /// Sets the width and height of a cell. For isometric maps this is the size of the diamond.
+    pub fn set_tile_size<T>(self: &mut Self, tile_size: T) -> &mut Self where Point2: From<T> {
+        self.tile_size = Point2::from(tile_size);
+        self.invalidate();
+        self
+    }
+
+    /// Returns the width and height of a cell.
+    pub fn tile_size(self: &Self) -> Point2 {
+        self.tile_size
+    }
+
+    /// Sets the sprite frame of the tile at given column and row, `None` removes the tile. Panics if the position is
+    /// outside of the map.
+    pub fn set_tile(self: &mut Self, x: u32, y: u32, frame_id: Option<u32>) -> &mut Self {
+        let index = self.index(x, y);
+        if self.tiles[index] != frame_id {
+            self.tiles[index] = frame_id;
+            let chunk_index = self.chunk_index(x / CHUNK_SIZE, y / CHUNK_SIZE);
+            // chunks are created once they receive their first tile
+            if self.chunks[chunk_index].is_none() {
+                self.chunks[chunk_index] = Some(TileChunk {
+                    layer   : Layer::with_capacity(self.dimensions, (CHUNK_SIZE * CHUNK_SIZE) as usize),
+                    dirty   : AtomicBool::new(true),
+                });
+            }
+            self.chunks[chunk_index].as_ref().unwrap().dirty.store(true, Ordering::Relaxed);
+        }
+        self
+    }
+
+    /// Returns the sprite frame of the tile at given column and row. Panics if the position is outside of the map.
+    pub fn tile(self: &Self, x: u32, y: u32) -> Option<u32> {
+        self.tiles[self.index(x, y)]
+    }
+
+    /// Returns the center of the cell at given column and row.
+    pub fn tile_position(self: &Self, x: u32, y: u32) -> Point2 {
+        let (x, y) = (x as f32, y as f32);
+        let (width, height) = self.tile_size;
+        match self.orientation {
+            TileOrientation::Orthogonal => ((x + 0.5) * width, (y + 0.5) * height),
+            TileOrientation::Isometric => ((x - y) * width / 2.0, (x + y + 1.0) * height / 2.0),
+            TileOrientation::Hexagonal => {
+                let shift = if y as u32 % 2 == 1 { width / 2.0 } else { 0.0 };
+                ((x + 0.5) * width + shift, (y * 0.75 + 0.5) * height)
+            }
+        }
+    }
+
+    /// Sets the view matrix. See [`Layer::set_view_matrix()`](struct.Layer.html#method.set_view_matrix).
+    pub fn set_view_matrix<T>(self: &Self, matrix: T) -> &Self where Mat4<f32>: From<T> {
+        self.view_matrix().set(&matrix.into());
+        self
+    }
+
+    /// Returns a mutex guarded mutable reference to the view matrix.
+    pub fn view_matrix(self: &Self) -> MutexGuard<Mat4Stack<f32>> {
+        self.view_matrix.lock().unwrap()
+    }
+
+    /// Sets a global color multiplicator. See [`Layer::set_color()`](struct.Layer.html#method.set_color).
+    pub fn set_color(self: &Self, color: Color) -> &Self {
+        self.color().set(color);
+        self
+    }
+
+    /// Returns a mutex guarded mutable reference to the global color multiplicator.
+    pub fn color(self: &Self) -> MutexGuard<Color> {
+        self.color.lock().unwrap()
+    }
+
+    /// Sets the blendmode.
+    pub fn set_blendmode(self: &Self, blendmode: BlendMode) -> &Self {
+        *self.blendmode() = blendmode;
+        self
+    }
+
+    /// Returns a mutex guarded mutable reference to the blendmode.
+    pub fn blendmode(self: &Self) -> MutexGuard<BlendMode> {
+        self.blend.lock().unwrap()
+    }
+
+    /// Creates one tile map per tile layer of given Tiled `.tmx` file. Tiles are mapped to the sprite frame named
+    /// after the file name of the tile's image (see [`Sprite::frame_id()`](struct.Sprite.html#method.frame_id)) or,
+    /// for tilesets consisting of a single image, to the frame at the tile's index within the tileset. Only one
+    /// tileset may consist of a single image.
+    ///
+    /// The tile size is taken from the map and may need to be adjusted to the size of the sprite using
+    /// [`set_tile_size()`](#method.set_tile_size). Layer opacity is applied as the tile map's color.
+    /// Returns `Error::TileMapError` if the file cannot be parsed or uses unsupported features.
+    #[cfg(feature = "tmx")]
+    pub fn from_tmx<T>(sprite: &Sprite, file: &str, dimensions: T) -> core::Result<Vec<Self>> where Point2: From<T> {
+        use tiled;
+
+        let dimensions = Point2::from(dimensions);
+        let map = tiled::parse(File::open(file)?).map_err(|error| core::Error::TileMapError(format!("Failed to parse {}: {:?}", file, error)))?;
+
+        let orientation = match map.orientation {
+            tiled::Orientation::Orthogonal => TileOrientation::Orthogonal,
+            tiled::Orientation::Isometric => TileOrientation::Isometric,
+            tiled::Orientation::Hexagonal => TileOrientation::Hexagonal,
+            tiled::Orientation::Staggered => return Err(core::Error::TileMapError("Staggered maps are not supported".to_string())),
+        };
+
+        // map global tile ids to sprite frames
+        let mut frames = HashMap::new();
+        let mut sheet_tileset = None;
+        for tileset in map.tilesets.iter() {
+            if tileset.tiles.iter().any(|tile| tile.images.len() > 0) {
+                for tile in tileset.tiles.iter() {
+                    if let Some(image) = tile.images.first() {
+                        let name = Path::new(&image.source).file_name().and_then(|name| name.to_str()).unwrap_or("");
+                        let frame_id = sprite.frame_id(name).ok_or_else(|| core::Error::TileMapError(format!("Sprite has no frame named {:?}", name)))?;
+                        frames.insert(tileset.first_gid + tile.id, frame_id);
+                    }
+                }
+            } else if let Some(other) = sheet_tileset.replace(&tileset.name) {
+                return Err(core::Error::TileMapError(format!("Tilesets {:?} and {:?} both use a sheet image, only one is supported", other, tileset.name)));
+            } else {
+                for frame_id in 0..cmp::min(sprite.num_frames(), tileset_tile_count(tileset)) {
+                    frames.insert(tileset.first_gid + frame_id, frame_id);
+                }
+            }
+        }
+
+        let mut result = Vec::new();
+
+        for layer in map.layers.iter() {
+            let mut tilemap = Self::new::<Point2>(sprite, (map.width, map.height), dimensions);
+            tilemap.set_orientation(orientation);
+            tilemap.set_tile_size::<Point2>((map.tile_width as f32, map.tile_height as f32));
+            tilemap.set_color(Color::alpha_pm(if layer.visible { layer.opacity } else { 0.0 }));
+            for (y, row) in layer.tiles.iter().enumerate().take(map.height as usize) {
+                for (x, &gid) in row.iter().enumerate().take(map.width as usize) {
+                    // upper bits store flipping flags
+                    let gid = gid & 0x1fff_ffff;
+                    if gid != 0 {
+                        let frame_id = frames.get(&gid).ok_or_else(|| core::Error::TileMapError(format!("Unknown tile id {}", gid)))?;
+                        tilemap.set_tile(x as u32, y as u32, Some(*frame_id));
+                    }
+                }
+            }
+            result.push(tilemap);
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the layers of all chunks that intersect the view, rebuilding chunks whose tiles changed.
+    pub(crate) fn visible_layers(self: &Self) -> Vec<&Layer> {
+
+        let view_matrix = **self.view_matrix();
+        let color = *self.color();
+        let blendmode = *self.blendmode();
+        let num_chunks = Self::num_chunks(self.size);
+        let mut result = Vec::new();
+
+        for chunk_y in 0..num_chunks.1 {
+            for chunk_x in 0..num_chunks.0 {
+                let chunk = match self.chunks[self.chunk_index(chunk_x, chunk_y)] {
+                    Some(ref chunk) if chunk_visible(self.chunk_bounds(chunk_x, chunk_y), &view_matrix) => chunk,
+                    _ => continue,
+                };
+                if chunk.dirty.swap(false, Ordering::Relaxed) {
+                    self.build_chunk(chunk_x, chunk_y, &chunk.layer);
+                }
+                chunk.layer.set_view_matrix(view_matrix);
+                chunk.layer.set_color(color);
+                chunk.layer.set_blendmode(blendmode);
+                result.push(&chunk.layer);
+            }
+        }
+
+        result
+    }
+
+    /// Redraws the tiles of given chunk onto given layer.
+    fn build_chunk(self: &Self, chunk_x: u32, chunk_y: u32, layer: &Layer) {
+        layer.clear();
+        let (x0, y0) = (chunk_x * CHUNK_SIZE, chunk_y * CHUNK_SIZE);
+        let (x1, y1) = ((x0 + CHUNK_SIZE).min(self.size.0), (y0 + CHUNK_SIZE).min(self.size.1));
+        let mut cells = Vec::new();
+        for y in y0..y1 {
+            for x in x0..x1 {
+                cells.push((x, y));
+            }
+        }
+        if self.orientation == TileOrientation::Isometric {
+            cells.sort_by_key(|&(x, y)| (x + y, x));
+        }
+        for (x, y) in cells {
+            if let Some(frame_id) = self.tiles[self.index(x, y)] {
+                self.sprite.draw(layer, frame_id, self.tile_position(x, y), Color::WHITE);
+            }
+        }
+    }
+
+    /// Returns a rectangle `((left, top), (right, bottom))` that contains all tiles of given chunk.
+    fn chunk_bounds(self: &Self, chunk_x: u32, chunk_y: u32) -> Rect {
+        let (x0, y0) = (chunk_x * CHUNK_SIZE, chunk_y * CHUNK_SIZE);
+        let (x1, y1) = ((x0 + CHUNK_SIZE).min(self.size.0) - 1, (y0 + CHUNK_SIZE).min(self.size.1) - 1);
+        let corners = [ self.tile_position(x0, y0), self.tile_position(x1, y0), self.tile_position(x0, y1), self.tile_position(x1, y1) ];
+        // tiles may extend up to a full frame from their cell's center in any direction
+        let margin = (self.tile_size.0 + self.sprite.width() as f32, self.tile_size.1 + self.sprite.height() as f32);
+        let left = corners.iter().fold(f32::INFINITY, |min, corner| min.min(corner.0));
+        let top = corners.iter().fold(f32::INFINITY, |min, corner| min.min(corner.1));
+        let right = corners.iter().fold(f32::NEG_INFINITY, |max, corner| max.max(corner.0));
+        let bottom = corners.iter().fold(f32::NEG_INFINITY, |max, corner| max.max(corner.1));
+        ((left - margin.0, top - margin.1), (right + margin.0, bottom + margin.1))
+    }
+
+    /// Flags all chunks for rebuilding.
+    fn invalidate(self: &Self) {
+        for chunk in self.chunks.iter().filter_map(|chunk| chunk.as_ref()) {
+            chunk.dirty.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the index of the tile at given position or panics.
+    fn index(self: &Self, x: u32, y: u32) -> usize {
+        assert!(x < self.size.0 && y < self.size.1, "Tile position ({}, {}) is outside of the map.", x, y);
+        (y * self.size.0 + x) as usize
+    }
+
+    /// Returns the index of given chunk.
+    fn chunk_index(self: &Self, chunk_x: u32, chunk_y: u32) -> usize {
+        (chunk_y * Self::num_chunks(self.size).0 + chunk_x) as usize
+    }
+
+    /// Returns the number of chunks required for a map of given size.
+    fn num_chunks(size: Point2<u32>) -> Point2<u32> {
+        ((size.0 + CHUNK_SIZE - 1) / CHUNK_SIZE, (size.1 + CHUNK_SIZE - 1) / CHUNK_SIZE)
+    }
+}
+
+/// Returns whether given rectangle `((left, top), (right, bottom))` intersects the view after transformation by
+/// given view matrix.
+fn chunk_visible(bounds: Rect, view_matrix: &Mat4) -> bool {
+    let ((left, top), (right, bottom)) = bounds;
+    core::layer::points_visible(&[ (left, top), (right, top), (left, bottom), (right, bottom) ], view_matrix)
+}
+
+/// Returns the number of tiles on the sheet image of given Tiled tileset.
+#[cfg(feature = "tmx")]
+fn tileset_tile_count(tileset: &::tiled::Tileset) -> u32 {
+    let count = |size: i32, tile_size: u32| {
+        let usable = cmp::max(0, size as i64 - 2 * tileset.margin as i64 + tileset.spacing as i64);
+        (usable / cmp::max(1, tile_size as i64 + tileset.spacing as i64)) as u32
+    };
+    tileset.images.iter().map(|image| count(image.width, tileset.tile_width).saturating_mul(count(image.height, tileset.tile_height))).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visibility() {
+        let viewport = Mat4::viewport(640.0, 480.0);
+        assert!(chunk_visible(((0.0, 0.0), (100.0, 100.0)), &viewport));
+        assert!(chunk_visible(((-100.0, -100.0), (1000.0, 1000.0)), &viewport));
+        assert!(chunk_visible(((600.0, 400.0), (700.0, 500.0)), &viewport));
+        assert!(!chunk_visible(((641.0, 0.0), (700.0, 100.0)), &viewport));
+        assert!(!chunk_visible(((0.0, -200.0), (100.0, -1.0)), &viewport));
+
+        // scrolled 1000 pixels to the right
+        let mut scrolled = viewport;
+        scrolled[3][0] -= 1000.0 * viewport[0][0];
+        assert!(!chunk_visible(((0.0, 0.0), (100.0, 100.0)), &scrolled));
+        assert!(chunk_visible(((1100.0, 0.0), (1200.0, 100.0)), &scrolled));
+    }
+}
@@ -88,6 +88,15 @@ component in the bottom row:
 
 ![Spritesheet](https://raw.githubusercontent.com/sinesc/radiant-rs/master/doc/spritesheet.png "Spritesheet")
 
+Sheets with named frames of arbitrary size, pivots and frame durations can be loaded from a JSON descriptor file (including
+TexturePacker and Aseprite exports) using [`Sprite::from_sheet_descriptor()`](struct.Sprite.html#method.from_sheet_descriptor).
+Frame sequences can be played back using an [`Animation`](struct.Animation.html) of named clips and a per-object
+[`AnimationState`](struct.AnimationState.html). Large grids of tiles are best drawn using a [`TileMap`](struct.TileMap.html),
+which caches its geometry in chunks and only renders the visible ones. Enable the `tmx` feature to load Tiled maps.
+
+By default, each frame is padded up to the next power of two. Use [`Context::set_sprite_storage()`](struct.Context.html#method.set_sprite_storage)
+with `SpriteStorage::Atlas` to pack frames of subsequently loaded sprites into shared atlas pages instead.
+
 # Custom shaders
 
 Radiant supports the use of custom fragment shaders. These are normal glsl shaders. To simplify access to the default
@@ -110,6 +119,9 @@ To access the default sampler, the following wrappers are provided:
 - `vec4 sheetComponent(in vec2 texture_coords, in uint component)` Samples a specific sprite
 component instead of the default one set by `Renderer::draw_layer()`.
 
+For untextured primitives drawn with e.g. `Layer::fill_rect()`, the wrappers return white for the first component. For meshes drawn with
+`Layer::draw_textured_mesh()`, they sample the texture given to `Renderer::draw_layer_with_texture()`.
+
 Example: (This is the default shader used by radiant.)
 
 ```text
@@ -149,6 +161,13 @@ Then use [`backend::target_frame`](backend/fn.target_frame.html) to direct the r
 Approach "less": Use [`backend::create_display()`](backend/fn.create_display.html) to create a Radiant Display from a Glium Display. Then use
 [`backend::take_frame()`](backend/fn.take_frame.html) to "borrow" a Glium Frame from Radiant. This approach let's you keep Radiant's window/event handling.
 
+# Headless rendering
+
+The `backend-software` feature replaces Glium with a CPU rasterizer that renders into memory instead of a window. It requires no GPU,
+which makes it suitable for automated tests on CI machines. Custom shaders are not executed by this backend. Build with
+`default-features = false, features = [ "backend-software" ]`, then use `Texture::read_pixels()` or `Display::read_pixels()` to
+inspect the rendered pixels.
+
 # Found and issue? Missing a feature?
 
 Please file a bug report if you encounter any issues with this library. In particular, it has only been tested on a limited number of graphics cards
@@ -163,9 +182,13 @@ extern crate image;
 extern crate regex;
 extern crate rusttype;
 extern crate unicode_normalization;
+extern crate xi_unicode;
 extern crate font_loader;
 extern crate avec;
 extern crate palette;
+extern crate serde_json;
+#[cfg(feature = "tmx")]
+extern crate tiled;
 #[cfg(feature = "serialize-serde")]
 extern crate serde;
 #[cfg(feature = "serialize-serde")]
@@ -1,8 +1,10 @@
 pub use core::{
     BlendMode, BlendingFunction, LinearBlendingFactor, blendmodes,
-    Display, Monitor,
+    Display, Monitor, Recorder,
     Renderer, RenderTarget, Context, AsRenderTarget,
-    Layer, Sprite, Font, Color,
+    Layer, LayerSortMode, MeshVertex, Sprite, SpriteHandle, SpriteStorage, Font, RichText, HorizontalAlign, VerticalAlign, Color,
+    Animation, AnimationMode, AnimationState, AnimationEvent,
+    TileMap, TileOrientation, Camera2D,
     Texture, TextureFormat, TextureFilter, TextureWrap,
     Program, Uniform, AsUniform,
     Postprocessor, postprocessors,
@@ -13,11 +15,19 @@ pub use core::{
 pub mod support {
     //! Support structures returned by various methods. Usually not required to be created manually.
     pub use core::{InputIterator, InputUpIterator, InputDownIterator};
-    pub use core::{DrawBuilder, DisplayBuilder, FontBuilder, FontQueryBuilder, TextureBuilder};
+    pub use core::{DrawBuilder, DisplayBuilder, FontBuilder, FontQueryBuilder, TextureBuilder, AnimationBuilder, SpriteDrawBuilder, SlicedDrawBuilder, TextBuilder};
     pub use core::{SpriteParameters, SpriteLayout};
+    pub use core::{TextMetrics, TextGlyph};
     pub use core::Mat4Stack;
 }
 
+pub mod testing {
+    //! Golden-image testing support. Renders draw calls to a texture and compares the result against stored reference images.
+    //!
+    //! Set the `RADIANT_UPDATE_SNAPSHOTS` environment variable to write new reference images instead of comparing.
+    pub use core::{Snapshot, UPDATE_SNAPSHOTS_VAR};
+}
+
 pub mod backend {
     //! Backend specific integration methods. Backends can be switched via [cargo features](http://doc.crates.io/manifest.html#the-features-section).
     //! The documentation shown here depends on the features it was generated with.
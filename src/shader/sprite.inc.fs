@@ -1,9 +1,7 @@
 uniform sampler2D _rd_tex;
-uniform sampler2DArray _rd_tex1;
-uniform sampler2DArray _rd_tex2;
-uniform sampler2DArray _rd_tex3;
-uniform sampler2DArray _rd_tex4;
-uniform sampler2DArray _rd_tex5;
+_RD_BUCKET_SAMPLERS
+uniform sampler2DArray _rd_atlas;
+uniform sampler2D _rd_layer_tex;
 uniform uint _rd_comp;
 
 flat in uint _rd_v_texture_id;
@@ -13,16 +11,15 @@ flat in uint _rd_v_components;
 ivec2 sheetSize() {
     if (_rd_v_bucket_id == 0u) {
         return textureSize(_rd_tex, 0);
-    } else if (_rd_v_bucket_id == 1u) {
-        return textureSize(_rd_tex1, 0).xy;
-    } else if (_rd_v_bucket_id == 2u) {
-        return textureSize(_rd_tex2, 0).xy;
-    } else if (_rd_v_bucket_id == 3u) {
-        return textureSize(_rd_tex3, 0).xy;
-    } else if (_rd_v_bucket_id == 4u) {
-        return textureSize(_rd_tex4, 0).xy;
-    } else /*if (_rd_v_bucket_id == 5u)*/ {
-        return textureSize(_rd_tex5, 0).xy;
+    } else if (_rd_v_bucket_id == 255u) {
+        return textureSize(_rd_atlas, 0).xy;
+    } else if (_rd_v_bucket_id == 254u) {
+        return ivec2(1, 1);
+    } else if (_rd_v_bucket_id == 253u) {
+        return textureSize(_rd_layer_tex, 0);
+_RD_BUCKET_SIZES
+    } else {
+        return ivec2(1, 1);
     }
 }
 
@@ -31,16 +28,15 @@ vec4 sheetComponent(in vec2 texture_coords, in uint component) {
         return texture(_rd_tex, texture_coords).rrrr;
     } else if (component >= _rd_v_components) {
         return vec4(0.0, 0.0, 0.0, 0.0);
-    } else if (_rd_v_bucket_id == 1u) {
-        return texture(_rd_tex1, vec3(texture_coords, float(_rd_v_texture_id + component)));
-    } else if (_rd_v_bucket_id == 2u) {
-        return texture(_rd_tex2, vec3(texture_coords, float(_rd_v_texture_id + component)));
-    } else if (_rd_v_bucket_id == 3u) {
-        return texture(_rd_tex3, vec3(texture_coords, float(_rd_v_texture_id + component)));
-    } else if (_rd_v_bucket_id == 4u) {
-        return texture(_rd_tex4, vec3(texture_coords, float(_rd_v_texture_id + component)));
-    } else /*if (_rd_v_bucket_id == 5u)*/ {
-        return texture(_rd_tex5, vec3(texture_coords, float(_rd_v_texture_id + component)));
+    } else if (_rd_v_bucket_id == 254u) {
+        return vec4(1.0, 1.0, 1.0, 1.0);
+    } else if (_rd_v_bucket_id == 253u) {
+        return texture(_rd_layer_tex, texture_coords);
+    } else if (_rd_v_bucket_id == 255u) {
+        return texture(_rd_atlas, vec3(texture_coords, float(_rd_v_texture_id + component)));
+_RD_BUCKET_TEXELS
+    } else {
+        return vec4(0.0, 0.0, 0.0, 0.0);
     }
 }
 
@@ -0,0 +1,93 @@
+//! Helpers shared by the integration tests.
+#![allow(dead_code)]
+
+use radiant_rs::*;
+use radiant_rs::support::{SpriteParameters, SpriteLayout};
+use image;
+
+/// Creates a single frame sprite of given size filled with white.
+pub fn white_sprite(context: &Context, size: u32) -> Sprite {
+    solid_sprite(context, (size, size), [ 255, 255, 255, 255 ])
+}
+
+/// Creates a single frame sprite of given dimensions filled with given color.
+pub fn solid_sprite(context: &Context, dimensions: (u32, u32), color: [u8; 4]) -> Sprite {
+    sprite_from_image(context, &image::RgbaImage::from_pixel(dimensions.0, dimensions.1, image::Rgba(color)), dimensions, (1, 1), 1)
+}
+
+/// Creates a sprite from given sheet image.
+pub fn sprite_from_image(context: &Context, sheet: &image::RgbaImage, dimensions: (u32, u32), num_frames: (u32, u32), components: u32) -> Sprite {
+    try_sprite_from_image(context, sheet, dimensions, num_frames, components).unwrap()
+}
+
+/// Creates a sprite from given sheet image, returning errors reported by `Sprite::from_data()`.
+pub fn try_sprite_from_image(context: &Context, sheet: &image::RgbaImage, dimensions: (u32, u32), num_frames: (u32, u32), components: u32) -> Result<Sprite> {
+    let mut data = Vec::new();
+    image::DynamicImage::ImageRgba8(sheet.clone()).write_to(&mut data, image::ImageFormat::PNG).unwrap();
+    let parameters = SpriteParameters {
+        dimensions  : dimensions,
+        num_frames  : num_frames,
+        components  : components,
+        inner_margin: 0,
+        layout      : SpriteLayout::HORIZONTAL,
+    };
+    Sprite::from_data(context, &data, &parameters)
+}
+
+/// Path of the font used by the text tests.
+pub const TEST_FONT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/DejaVuSans-subset.ttf");
+
+/// Loads the test font at given size.
+pub fn test_font(context: &Context, size: f32) -> Font {
+    match Font::from_file(context, TEST_FONT) {
+        Ok(font) => font.clone_with_size(size),
+        Err(error) => panic!("failed to load test font {}: {:?}", TEST_FONT, error),
+    }
+}
+
+/// Fills given rectangle `((x, y), (width, height))` of an image with given color.
+pub fn fill_image(image: &mut image::RgbaImage, ((x, y), (width, height)): ((u32, u32), (u32, u32)), color: [u8; 4]) {
+    for py in y..y + height {
+        for px in x..x + width {
+            image.put_pixel(px, py, image::Rgba(color));
+        }
+    }
+}
+
+/// A hidden display and a renderer for it.
+pub struct TestDisplay {
+    pub display : Display,
+    pub renderer: Renderer,
+}
+
+impl TestDisplay {
+
+    /// Creates a hidden display of given dimensions and a renderer for it.
+    pub fn new(dimensions: (u32, u32)) -> TestDisplay {
+        let display = Display::builder().dimensions(dimensions).hidden().build().unwrap();
+        let renderer = Renderer::new(&display).unwrap();
+        TestDisplay {
+            display : display,
+            renderer: renderer,
+        }
+    }
+
+    /// Returns the display's context.
+    pub fn context(self: &Self) -> &Context {
+        self.display.context()
+    }
+
+    /// Renders a frame cleared to black using given draw function and returns its pixels.
+    pub fn render<F>(self: &Self, draw_func: F) -> image::RgbaImage where F: FnOnce(&Renderer) {
+        self.display.clear_frame(Color::BLACK);
+        draw_func(&self.renderer);
+        let pixels = self.display.read_pixels();
+        self.display.swap_frame();
+        pixels
+    }
+
+    /// Renders given layer onto a frame cleared to black and returns its pixels.
+    pub fn render_layer(self: &Self, layer: &Layer) -> image::RgbaImage {
+        self.render(|renderer| { renderer.draw_layer(layer, 0); })
+    }
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "backend-software")]
+extern crate radiant_rs;
+extern crate image;
+use radiant_rs::*;
+use radiant_rs::testing::Snapshot;
+use std::env;
+
+mod common;
+use common::*;
+
+#[test]
+fn rect() {
+    let display = Display::builder().dimensions((32, 32)).hidden().build().unwrap();
+    let renderer = Renderer::new(&display).unwrap();
+
+    let snapshot = Snapshot::render(&renderer, (32, 32), || {
+        renderer.clear(Color::BLACK);
+        renderer.rect(((8., 8.), (16., 16.))).color(Color::RED).draw();
+    });
+
+    snapshot.assert_matches("tests/snapshots/rect.png", 0);
+}
+
+#[test]
+fn sprite_add() {
+    let display = Display::builder().dimensions((32, 32)).hidden().build().unwrap();
+    let renderer = Renderer::new(&display).unwrap();
+    let sprite = white_sprite(display.context(), 8);
+    let layer = Layer::new((32., 32.));
+    layer.set_blendmode(blendmodes::ADD);
+    sprite.draw(&layer, 0, (16., 16.), Color::GREEN);
+
+    let snapshot = Snapshot::render(&renderer, (32, 32), || {
+        renderer.clear(Color(0.2, 0.2, 0.2, 1.0));
+        renderer.draw_layer(&layer, 0);
+    });
+
+    snapshot.assert_matches("tests/snapshots/sprite_add.png", 1);
+}
+
+#[test]
+fn mismatch_writes_diff() {
+    let reference = env::temp_dir().join("radiant_snapshot_mismatch.png");
+    let reference = reference.to_str().unwrap();
+    Snapshot::from_image(image::RgbaImage::from_pixel(4, 4, image::Rgba([ 0, 0, 0, 255 ]))).save(reference).unwrap();
+
+    let mut actual = image::RgbaImage::from_pixel(4, 4, image::Rgba([ 0, 0, 0, 255 ]));
+    actual.put_pixel(1, 1, image::Rgba([ 10, 0, 0, 255 ]));
+    actual.put_pixel(2, 2, image::Rgba([ 2, 0, 0, 255 ]));
+
+    let mismatches = Snapshot::from_image(actual).compare(reference, 2).unwrap();
+    assert_eq!(mismatches, 1);
+    assert!(env::temp_dir().join("radiant_snapshot_mismatch.actual.png").exists());
+    assert!(env::temp_dir().join("radiant_snapshot_mismatch.expected.png").exists());
+    assert!(env::temp_dir().join("radiant_snapshot_mismatch.diff.png").exists());
+}
+
+#[test]
+fn sprite_transforms() {
+    let target = TestDisplay::new((64, 32));
+    let mut sheet = image::RgbaImage::from_pixel(8, 8, image::Rgba([ 255, 255, 255, 255 ]));
+    fill_image(&mut sheet, ((0, 0), (4, 4)), [ 255, 0, 0, 255 ]);
+    fill_image(&mut sheet, ((4, 0), (4, 4)), [ 0, 255, 0, 255 ]);
+    fill_image(&mut sheet, ((0, 4), (4, 4)), [ 0, 0, 255, 255 ]);
+    let sprite = sprite_from_image(target.context(), &sheet, (8, 8), (1, 1), 1);
+    let layer = Layer::new((64., 32.));
+    sprite.draw_builder(&layer, 0).position((8., 8.)).draw();
+    sprite.draw_builder(&layer, 0).position((24., 8.)).flip_x().draw();
+    sprite.draw_builder(&layer, 0).position((40., 8.)).flip_y().rotation(0.5).draw();
+    sprite.draw_builder(&layer, 0).position((56., 8.)).crop(((0.5, 0.), (0.5, 1.))).scale((2., 1.)).draw();
+    sprite.draw_builder(&layer, 0).position((8., 24.)).skew((0.5, 0.)).draw();
+    sprite.draw_builder(&layer, 0).position((24., 24.)).anchor((0., 0.)).scale((1.5, 1.)).draw();
+    sprite.draw_builder(&layer, 0).position((48., 24.)).scale((2., 1.)).gradient_x(Color::WHITE, Color(1., 1., 1., 0.)).draw();
+
+    let snapshot = Snapshot::render(&target.renderer, (64, 32), || {
+        target.renderer.clear(Color::BLACK);
+        target.renderer.draw_layer(&layer, 0);
+    });
+
+    snapshot.assert_matches("tests/snapshots/sprite_transforms.png", 1);
+}
+
+#[test]
+fn sprite_sliced() {
+    let target = TestDisplay::new((64, 32));
+    let mut sheet = image::RgbaImage::from_pixel(12, 12, image::Rgba([ 0, 0, 255, 255 ]));
+    fill_image(&mut sheet, ((4, 4), (4, 4)), [ 255, 255, 255, 255 ]);
+    fill_image(&mut sheet, ((0, 0), (4, 4)), [ 255, 0, 0, 255 ]);
+    fill_image(&mut sheet, ((9, 9), (3, 3)), [ 0, 255, 0, 255 ]);
+    let sprite = sprite_from_image(target.context(), &sheet, (12, 12), (1, 1), 1);
+    let layer = Layer::new((64., 32.));
+    sprite.draw_sliced(&layer, 0, ((2., 2.), (28., 28.))).insets((4., 4., 4., 4.)).draw();
+    sprite.draw_sliced(&layer, 0, ((34., 2.), (28., 20.))).insets((4., 4., 4., 4.)).tile().color(Color(1., 1., 0.5, 1.)).draw();
+
+    let snapshot = Snapshot::render(&target.renderer, (64, 32), || {
+        target.renderer.clear(Color::BLACK);
+        target.renderer.draw_layer(&layer, 0);
+    });
+
+    snapshot.assert_matches("tests/snapshots/sprite_sliced.png", 1);
+}
+
+#[test]
+fn blendmodes() {
+    let display = Display::builder().dimensions((64, 16)).hidden().build().unwrap();
+    let renderer = Renderer::new(&display).unwrap();
+    let sprite = white_sprite(display.context(), 8);
+    let modes = [
+        blendmodes::COPY, blendmodes::ALPHA, blendmodes::ADD, blendmodes::SUBTRACT,
+        blendmodes::REVERSE_SUBTRACT, blendmodes::LIGHTEN, blendmodes::DARKEN, blendmodes::SCREEN,
+    ];
+
+    // a red and a half transparent green stripe, overdrawn by a column of half transparent blue per blendmode
+    let layers: Vec<Layer> = modes.iter().enumerate().map(|(index, &mode)| {
+        let layer = Layer::new((64., 16.));
+        layer.set_blendmode(mode);
+        sprite.draw_transformed(&layer, 0, (index as f32 * 8. + 4., 8.), Color(0.1, 0.2, 0.4, 0.5), 0., (1., 2.));
+        layer
+    }).collect();
+
+    let snapshot = Snapshot::render(&renderer, (64, 16), || {
+        renderer.clear(Color(0.2, 0.2, 0.2, 1.0));
+        renderer.rect(((0., 0.), (64., 8.))).color(Color(0.8, 0.2, 0.2, 1.0)).draw();
+        renderer.rect(((0., 8.), (64., 8.))).color(Color(0.0, 0.5, 0.0, 0.5)).draw();
+        for layer in &layers {
+            renderer.draw_layer(layer, 0);
+        }
+    });
+
+    snapshot.assert_matches("tests/snapshots/blendmodes.png", 1);
+}
+
+#[test]
+fn text() {
+    let target = TestDisplay::new((112, 48));
+    let font = test_font(target.context(), 12.0);
+    let layer = Layer::new((112., 48.));
+    font.write_builder(&layer, "Left").position((2., 2.)).write();
+    font.write_builder(&layer, "Center").position((56., 2.)).align(HorizontalAlign::Center).color(Color::YELLOW).write();
+    font.write_builder(&layer, "Right").position((110., 2.)).align(HorizontalAlign::Right).write();
+    font.write_builder(&layer, "Wrapped text").position((2., 18.)).max_width(52.).color(Color(0.5, 1.0, 0.5, 1.0)).write();
+    let rich = RichText::from_markup("[color=#f00]Rich[/color] [size=16]Text[/size]", &font, Color::WHITE, &[]).unwrap();
+    rich.write(&layer, (56., 20.));
+
+    let snapshot = Snapshot::render(&target.renderer, (112, 48), || {
+        target.renderer.clear(Color::BLACK);
+        target.renderer.draw_layer(&layer, 0);
+    });
+
+    snapshot.assert_matches("tests/snapshots/text.png", 1);
+}
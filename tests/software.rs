@@ -0,0 +1,829 @@
+#![cfg(feature = "backend-software")]
+extern crate radiant_rs;
+extern crate image;
+use radiant_rs::*;
+
+mod common;
+use common::*;
+
+#[test]
+fn draw_rect_to_texture() {
+    let display = Display::builder().dimensions((32, 32)).hidden().build().unwrap();
+    let renderer = Renderer::new(&display).unwrap();
+    let target = Texture::new(display.context(), 32, 32);
+
+    renderer.render_to(&target, || {
+        renderer.clear(Color::BLACK);
+        renderer.rect(((8., 8.), (16., 16.))).color(Color::RED).draw();
+    });
+
+    let pixels = target.read_pixels();
+    assert_eq!(pixels.get_pixel(2, 2).data, [ 0, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(8, 8).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(23, 23).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(24, 24).data, [ 0, 0, 0, 255 ]);
+}
+
+#[test]
+fn draw_layer_to_display() {
+    let display = Display::builder().dimensions((32, 32)).hidden().build().unwrap();
+    let renderer = Renderer::new(&display).unwrap();
+    let sprite = white_sprite(display.context(), 8);
+    let layer = Layer::new((32., 32.));
+    sprite.draw(&layer, 0, (16., 16.), Color::GREEN);
+
+    display.clear_frame(Color::BLACK);
+    renderer.draw_layer(&layer, 0);
+    display.swap_frame();
+
+    let pixels = backend::read_display(&display);
+    assert_eq!(pixels.get_pixel(2, 2).data, [ 0, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(16, 16).data, [ 0, 255, 0, 255 ]);
+}
+
+#[test]
+fn read_texture_rect() {
+    let display = Display::builder().dimensions((32, 32)).hidden().build().unwrap();
+    let renderer = Renderer::new(&display).unwrap();
+    let target = Texture::new(display.context(), 32, 32);
+
+    renderer.render_to(&target, || {
+        renderer.clear(Color::BLACK);
+        renderer.rect(((0., 0.), (8., 4.))).color(Color::RED).draw();
+    });
+
+    let pixels = target.read_rect(((6, 2), (4, 4)));
+    assert_eq!(pixels.dimensions(), (4, 4));
+    assert_eq!(pixels.get_pixel(0, 0).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(1, 1).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(2, 1).data, [ 0, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(1, 2).data, [ 0, 0, 0, 255 ]);
+}
+
+#[test]
+fn read_current_frame() {
+    let display = Display::builder().dimensions((32, 32)).hidden().build().unwrap();
+    let renderer = Renderer::new(&display).unwrap();
+
+    display.clear_frame(Color::BLUE);
+    renderer.rect(((16., 0.), (16., 32.))).color(Color::WHITE).draw();
+
+    let pixels = display.read_pixels();
+    assert_eq!(pixels.dimensions(), (32, 32));
+    assert_eq!(pixels.get_pixel(0, 0).data, [ 0, 0, 255, 255 ]);
+    assert_eq!(pixels.get_pixel(31, 31).data, [ 255, 255, 255, 255 ]);
+
+    let pixels = display.read_rect(((15, 10), (2, 1)));
+    assert_eq!(pixels.get_pixel(0, 0).data, [ 0, 0, 255, 255 ]);
+    assert_eq!(pixels.get_pixel(1, 0).data, [ 255, 255, 255, 255 ]);
+
+    display.swap_frame();
+}
+
+#[test]
+#[should_panic(expected = "Rectangle exceeds display dimensions.")]
+fn read_rect_out_of_bounds() {
+    let display = Display::builder().dimensions((32, 32)).hidden().build().unwrap();
+    display.clear_frame(Color::BLACK);
+    display.read_rect(((16, 16), (17, 8)));
+}
+
+#[test]
+fn record_frames() {
+    let display = Display::builder().dimensions((16, 16)).hidden().build().unwrap();
+    let prefix = std::env::temp_dir().join("radiant_record_frames_");
+    let prefix = prefix.to_str().unwrap();
+
+    display.start_recording(Recorder::new(prefix, 2));
+    for frame in 0..5 {
+        display.clear_frame(if frame == 2 { Color::RED } else { Color::BLACK });
+        display.swap_frame();
+    }
+    assert_eq!(display.stop_recording().unwrap(), 3);
+
+    let captured = image::open(format!("{}00001.png", prefix)).unwrap().to_rgba();
+    assert_eq!(captured.get_pixel(8, 8).data, [ 255, 0, 0, 255 ]);
+}
+
+#[test]
+fn screenshot() {
+    let display = Display::builder().dimensions((16, 16)).hidden().build().unwrap();
+    let file = std::env::temp_dir().join("radiant_screenshot.png");
+    let file = file.to_str().unwrap();
+
+    display.clear_frame(Color::GREEN);
+    display.screenshot(file).unwrap();
+    display.swap_frame();
+
+    let captured = image::open(file).unwrap().to_rgba();
+    assert_eq!(captured.dimensions(), (16, 16));
+    assert_eq!(captured.get_pixel(0, 15).data, [ 0, 255, 0, 255 ]);
+}
+
+#[test]
+fn atlas_storage() {
+    let target = TestDisplay::new((32, 32));
+    target.context().set_sprite_storage(SpriteStorage::Atlas { padding: 1 });
+    let red = solid_sprite(target.context(), (5, 3), [ 255, 0, 0, 255 ]);
+    let blue = solid_sprite(target.context(), (7, 7), [ 0, 0, 255, 255 ]);
+    let layer = Layer::new((32., 32.));
+    red.draw(&layer, 0, (8., 8.), Color::WHITE);
+    blue.draw(&layer, 0, (24., 24.), Color::WHITE);
+
+    let pixels = target.render_layer(&layer);
+
+    assert_eq!(pixels.get_pixel(1, 1).data, [ 0, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(6, 7).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(8, 8).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(8, 11).data, [ 0, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(21, 21).data, [ 0, 0, 255, 255 ]);
+    assert_eq!(pixels.get_pixel(24, 24).data, [ 0, 0, 255, 255 ]);
+}
+
+#[test]
+fn atlas_prune() {
+    let target = TestDisplay::new((32, 32));
+    target.context().set_sprite_storage(SpriteStorage::Atlas { padding: 0 });
+    let dropped = solid_sprite(target.context(), (1100, 1100), [ 255, 0, 0, 255 ]);
+    let kept = solid_sprite(target.context(), (1100, 1100), [ 0, 255, 0, 255 ]);
+    drop(dropped);
+    target.context().prune();
+
+    let layer = Layer::new((32., 32.));
+    kept.draw(&layer, 0, (16., 16.), Color::WHITE);
+    let pixels = target.render_layer(&layer);
+
+    assert_eq!(pixels.get_pixel(16, 16).data, [ 0, 255, 0, 255 ]);
+}
+
+#[test]
+fn large_sprite() {
+    let target = TestDisplay::new((32, 32));
+    let sprite = solid_sprite(target.context(), (600, 20), [ 0, 255, 0, 255 ]);
+    let layer = Layer::new((32., 32.));
+    sprite.draw(&layer, 0, (16., 16.), Color::WHITE);
+
+    let pixels = target.render_layer(&layer);
+
+    assert_eq!(pixels.get_pixel(0, 16).data, [ 0, 255, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(0, 0).data, [ 0, 0, 0, 255 ]);
+}
+
+#[test]
+fn oversized_sprite() {
+    let display = Display::builder().dimensions((32, 32)).hidden().build().unwrap();
+    match try_sprite_from_image(display.context(), &image::RgbaImage::new(4097, 1), (4097, 1), (1, 1), 1) {
+        Err(Error::SpriteSizeError(_)) => { }
+        other => panic!("expected SpriteSizeError, got {:?}", other),
+    }
+    display.context().set_sprite_storage(SpriteStorage::Atlas { padding: 1 });
+    match try_sprite_from_image(display.context(), &image::RgbaImage::new(2047, 1), (2047, 1), (1, 1), 1) {
+        Err(Error::SpriteSizeError(_)) => { }
+        other => panic!("expected SpriteSizeError, got {:?}", other),
+    }
+}
+
+fn write_sheet(name: &str, descriptor: &str) -> String {
+    let directory = std::env::temp_dir();
+    let mut sheet = image::RgbaImage::from_pixel(12, 8, image::Rgba([ 0, 0, 255, 255 ]));
+    fill_image(&mut sheet, ((0, 0), (4, 4)), [ 255, 0, 0, 255 ]);
+    sheet.save(directory.join(format!("{}.png", name))).unwrap();
+    let file = directory.join(format!("{}.json", name));
+    std::fs::write(&file, descriptor.replace("IMAGE", &format!("{}.png", name))).unwrap();
+    file.to_str().unwrap().to_string()
+}
+
+#[test]
+fn sheet_descriptor() {
+    let target = TestDisplay::new((32, 32));
+    let file = write_sheet("radiant_sheet_descriptor", r#"{
+        "image": "IMAGE",
+        "frames": [
+            { "name": "red", "x": 0, "y": 0, "w": 4, "h": 4, "duration": 50 },
+            { "name": "blue", "x": 4, "y": 0, "w": 8, "h": 8, "pivot": [ 0, 0 ] }
+        ]
+    }"#);
+    let sprite = Sprite::from_sheet_descriptor(target.context(), &file).unwrap();
+
+    assert_eq!(sprite.num_frames(), 2);
+    assert_eq!(sprite.frame_id("blue"), Some(1));
+    assert_eq!(sprite.frame_id("green"), None);
+    assert_eq!(sprite.frame_dimensions(0), (4, 4));
+    assert_eq!(sprite.frame_duration(0), Some(0.05));
+    assert_eq!(sprite.frame_duration(1), None);
+
+    let layer = Layer::new((32., 32.));
+    sprite.draw(&layer, 0, (4., 4.), Color::WHITE);
+    sprite.draw(&layer, 1, (16., 16.), Color::WHITE);
+
+    let pixels = target.render_layer(&layer);
+
+    assert_eq!(pixels.get_pixel(2, 2).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(6, 6).data, [ 0, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(15, 15).data, [ 0, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(16, 16).data, [ 0, 0, 255, 255 ]);
+    assert_eq!(pixels.get_pixel(23, 23).data, [ 0, 0, 255, 255 ]);
+    assert_eq!(pixels.get_pixel(24, 24).data, [ 0, 0, 0, 255 ]);
+}
+
+#[test]
+fn texturepacker_descriptor() {
+    let display = Display::builder().dimensions((32, 32)).hidden().build().unwrap();
+    let file = write_sheet("radiant_texturepacker_descriptor", r#"{
+        "frames": {
+            "ship_1.png": { "frame": { "x": 4, "y": 0, "w": 8, "h": 8 }, "rotated": false, "trimmed": false, "duration": 100 },
+            "ship_0.png": { "frame": { "x": 0, "y": 0, "w": 4, "h": 4 }, "rotated": false, "trimmed": false, "duration": 100 }
+        },
+        "meta": { "image": "IMAGE", "size": { "w": 12, "h": 8 } }
+    }"#);
+    let sprite = Sprite::from_sheet_descriptor(display.context(), &file).unwrap();
+
+    assert_eq!(sprite.frame_id("ship_1.png"), Some(0));
+    assert_eq!(sprite.frame_id("ship_0.png"), Some(1));
+    assert_eq!((sprite.width(), sprite.height()), (8, 8));
+    assert_eq!(sprite.frame_duration(1), Some(0.1));
+}
+
+#[test]
+fn invalid_descriptor() {
+    let display = Display::builder().dimensions((32, 32)).hidden().build().unwrap();
+    let file = write_sheet("radiant_invalid_descriptor", r#"{ "image": "IMAGE", "frames": [ { "x": 8, "y": 0, "w": 8, "h": 8 } ] }"#);
+    match Sprite::from_sheet_descriptor(display.context(), &file) {
+        Err(Error::SpriteLayoutError(_)) => { }
+        other => panic!("expected SpriteLayoutError, got {:?}", other),
+    }
+}
+
+#[test]
+fn named_frames() {
+    let target = TestDisplay::new((16, 16));
+    let directory = std::env::temp_dir();
+    let mut sheet = image::RgbaImage::from_pixel(8, 4, image::Rgba([ 255, 0, 0, 255 ]));
+    fill_image(&mut sheet, ((4, 0), (4, 4)), [ 0, 255, 0, 255 ]);
+    let file = directory.join("radiant_named_frames_4x4.png");
+    sheet.save(&file).unwrap();
+    std::fs::write(directory.join("radiant_named_frames_4x4.txt"), "red.png\ngreen.png\n").unwrap();
+    let sprite = Sprite::from_file(target.context(), file.to_str().unwrap()).unwrap();
+
+    assert_eq!(sprite.frame_id("red.png"), Some(0));
+    assert_eq!(sprite.frame_id("green.png"), Some(1));
+
+    let layer = Layer::new((16., 16.));
+    sprite.draw_named(&layer, "green.png", (8., 8.), Color::WHITE);
+    let pixels = target.render_layer(&layer);
+
+    assert_eq!(pixels.get_pixel(8, 8).data, [ 0, 255, 0, 255 ]);
+}
+
+#[test]
+fn animation_modes() {
+    let display = Display::builder().dimensions((16, 16)).hidden().build().unwrap();
+    let sprite = sprite_from_image(display.context(), &image::RgbaImage::new(16, 4), (4, 4), (4, 1), 1);
+    let animation = Animation::builder(&sprite)
+        .frame_duration(0.25)
+        .clip("loop", 0..4, AnimationMode::Loop)
+        .clip("once", vec![ 3, 2 ], AnimationMode::Once)
+        .clip("pingpong", 0..4, AnimationMode::PingPong)
+        .timed_clip("timed", vec![ (1, 0.5), (2, 1.0) ], AnimationMode::Loop)
+        .build();
+
+    assert_eq!(animation.clip_duration("loop"), Some(1.0));
+    assert_eq!(animation.clip_duration("pingpong"), Some(1.5));
+    assert_eq!(animation.clip_duration("timed"), Some(1.5));
+    assert!(!animation.has_clip("missing"));
+
+    let mut state = animation.state("loop");
+    assert_eq!(state.frame_id(&animation), 0);
+    assert_eq!(state.update(&animation, 0.5), None);
+    assert_eq!(state.frame_id(&animation), 2);
+    assert_eq!(state.update(&animation, 2.6), Some(AnimationEvent::Looped(3)));
+    assert_eq!(state.frame_id(&animation), 0);
+
+    state.play(&animation, "once");
+    state.set_speed(2.0);
+    assert_eq!(state.frame_id(&animation), 3);
+    assert_eq!(state.update(&animation, 0.2), None);
+    assert_eq!(state.update(&animation, 0.1), Some(AnimationEvent::Finished));
+    assert!(state.is_finished());
+    assert_eq!(state.frame_id(&animation), 2);
+    assert_eq!(state.update(&animation, 1.0), None);
+
+    let mut state = animation.state("pingpong");
+    let frames: Vec<u32> = (0..6).map(|_| { let frame_id = state.frame_id(&animation); state.update(&animation, 0.25); frame_id }).collect();
+    assert_eq!(frames, vec![ 0, 1, 2, 3, 2, 1 ]);
+    assert_eq!(state.frame_id(&animation), 0);
+    assert_eq!(state.clip(&animation), "pingpong");
+
+    let mut state = animation.state("timed");
+    state.update(&animation, 0.75);
+    assert_eq!(state.frame_id(&animation), 2);
+}
+
+#[test]
+fn sprite_draw_builder() {
+    let target = TestDisplay::new((32, 16));
+    let mut sheet = image::RgbaImage::from_pixel(8, 4, image::Rgba([ 255, 0, 0, 255 ]));
+    fill_image(&mut sheet, ((4, 0), (4, 4)), [ 0, 255, 0, 255 ]);
+    let sprite = sprite_from_image(target.context(), &sheet, (8, 4), (1, 1), 1);
+    let layer = Layer::new((32., 16.));
+    sprite.draw_builder(&layer, 0).position((8., 4.)).flip_x().draw();
+    sprite.draw_builder(&layer, 0).position((24., 4.)).crop(((0., 0.), (0.5, 1.))).draw();
+    sprite.draw_builder(&layer, 0).position((8., 12.)).anchor((0., 0.5)).draw();
+
+    let pixels = target.render_layer(&layer);
+
+    // flipped: green left, red right
+    assert_eq!(pixels.get_pixel(5, 4).data, [ 0, 255, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(10, 4).data, [ 255, 0, 0, 255 ]);
+    // cropped: red left half only
+    assert_eq!(pixels.get_pixel(21, 4).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(26, 4).data, [ 0, 0, 0, 255 ]);
+    // anchor override: left edge at x = 8
+    assert_eq!(pixels.get_pixel(7, 12).data, [ 0, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(9, 12).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(14, 12).data, [ 0, 255, 0, 255 ]);
+}
+
+#[test]
+fn sprite_corner_colors() {
+    let target = TestDisplay::new((32, 16));
+    let sprite = white_sprite(target.context(), 16);
+    let layer = Layer::new((32., 16.));
+    sprite.draw_builder(&layer, 0).position((8., 8.)).gradient_x(Color::RED, Color::BLUE).draw();
+    sprite.draw_builder(&layer, 0).position((24., 8.)).gradient_x(Color::RED, Color::BLUE).flip_x().draw();
+
+    let pixels = target.render_layer(&layer);
+
+    let left = pixels.get_pixel(0, 8).data;
+    let right = pixels.get_pixel(15, 8).data;
+    assert!(left[0] > 240 && left[2] < 15, "{:?}", left);
+    assert!(right[0] < 15 && right[2] > 240, "{:?}", right);
+    let middle = pixels.get_pixel(8, 8).data;
+    assert!(middle[0] > 100 && middle[0] < 155 && middle[2] > 100 && middle[2] < 155, "{:?}", middle);
+    // flipping does not move the corner colors
+    let flipped_left = pixels.get_pixel(16, 8).data;
+    assert!(flipped_left[0] > 240 && flipped_left[2] < 15, "{:?}", flipped_left);
+}
+
+#[test]
+fn sprite_component() {
+    let target = TestDisplay::new((16, 16));
+    let mut sheet = image::RgbaImage::from_pixel(4, 8, image::Rgba([ 255, 0, 0, 255 ]));
+    fill_image(&mut sheet, ((0, 4), (4, 4)), [ 0, 255, 0, 255 ]);
+    let sprite = sprite_from_image(target.context(), &sheet, (4, 4), (1, 1), 2);
+    assert_eq!(sprite.num_components(), 2);
+    let layer = Layer::new((16., 16.));
+    sprite.draw_builder(&layer, 0).position((4., 8.)).draw();
+    sprite.draw_builder(&layer, 0).position((12., 8.)).component(1).draw();
+
+    let pixels = target.render_layer(&layer);
+
+    assert_eq!(pixels.get_pixel(4, 8).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(12, 8).data, [ 0, 255, 0, 255 ]);
+}
+
+#[test]
+fn primitives() {
+    let target = TestDisplay::new((64, 64));
+    let sprite = white_sprite(target.context(), 4);
+    let layer = Layer::new((64., 64.));
+    layer.fill_rect(((2., 2.), (12., 8.)), Color::RED, 0.);
+    layer.stroke_rect(((20., 2.), (12., 12.)), 2., Color::GREEN, 0.);
+    layer.line((2., 20.), (30., 20.), 2., Color::BLUE, 0.);
+    layer.fill_circle((48., 16.), 10., Color::WHITE, 0.);
+    layer.stroke_circle((16., 48.), 10., 2., Color::RED, 0.);
+    layer.fill_polygon(&[ (40., 40.), (60., 40.), (50., 60.) ], Color::GREEN, 0.);
+    sprite.draw(&layer, 0, (48., 16.), Color::BLUE);
+
+    let pixels = target.render_layer(&layer);
+
+    // filled rectangle
+    assert_eq!(pixels.get_pixel(2, 2).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(13, 9).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(14, 9).data, [ 0, 0, 0, 255 ]);
+    // outlined rectangle
+    assert_eq!(pixels.get_pixel(20, 8).data, [ 0, 255, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(26, 13).data, [ 0, 255, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(26, 8).data, [ 0, 0, 0, 255 ]);
+    // line
+    assert_eq!(pixels.get_pixel(16, 19).data, [ 0, 0, 255, 255 ]);
+    assert_eq!(pixels.get_pixel(16, 20).data, [ 0, 0, 255, 255 ]);
+    assert_eq!(pixels.get_pixel(16, 22).data, [ 0, 0, 0, 255 ]);
+    // filled circle with a sprite on top, batched in the same layer
+    assert_eq!(pixels.get_pixel(48, 8).data, [ 255, 255, 255, 255 ]);
+    assert_eq!(pixels.get_pixel(48, 16).data, [ 0, 0, 255, 255 ]);
+    assert_eq!(pixels.get_pixel(40, 8).data, [ 0, 0, 0, 255 ]);
+    // outlined circle
+    assert_eq!(pixels.get_pixel(16, 38).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(26, 48).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(16, 48).data, [ 0, 0, 0, 255 ]);
+    // polygon
+    assert_eq!(pixels.get_pixel(50, 45).data, [ 0, 255, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(42, 55).data, [ 0, 0, 0, 255 ]);
+}
+
+#[test]
+fn meshes() {
+    let target = TestDisplay::new((64, 64));
+    let sprite = solid_sprite(target.context(), (4, 4), [ 0, 0, 255, 255 ]);
+    let texture = Texture::new(target.context(), 8, 8);
+    target.renderer.render_to(&texture, || {
+        target.renderer.clear(Color::GREEN);
+    });
+
+    let quad = |color: Color| vec![
+        MeshVertex::new((-6., -6.), (0., 0.), color),
+        MeshVertex::new((6., -6.), (1., 0.), color),
+        MeshVertex::new((-6., 6.), (0., 1.), color),
+        MeshVertex::new((6., 6.), (1., 1.), color),
+    ];
+    let layer = Layer::new((64., 64.));
+    layer.draw_mesh((8., 8.), &quad(Color::RED), &[ 0, 1, 2, 1, 3, 2 ], 0.);
+    sprite.draw_mesh(&layer, 0, (32., 8.), &quad(Color::WHITE), &[ 0, 1, 2 ], 0.);
+    layer.draw_textured_mesh((8., 40.), &quad(Color::WHITE), &[ 2, 1, 0, 3, 2, 1 ], 0.);
+    assert_eq!(layer.len(), 3);
+
+    let pixels = target.render(|renderer| { renderer.draw_layer_with_texture(&layer, 0, &texture); });
+
+    // untextured quad
+    assert_eq!(pixels.get_pixel(3, 3).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(12, 12).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(15, 8).data, [ 0, 0, 0, 255 ]);
+    // single triangle textured with a sprite frame
+    assert_eq!(pixels.get_pixel(28, 4).data, [ 0, 0, 255, 255 ]);
+    assert_eq!(pixels.get_pixel(36, 12).data, [ 0, 0, 0, 255 ]);
+    // quad textured with the layer texture
+    assert_eq!(pixels.get_pixel(8, 40).data, [ 0, 255, 0, 255 ]);
+
+    // without a texture, textured meshes are transparent
+    let pixels = target.render_layer(&layer);
+    assert_eq!(pixels.get_pixel(8, 8).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(8, 40).data, [ 0, 0, 0, 255 ]);
+}
+
+#[test]
+fn sliced_sprite() {
+    let target = TestDisplay::new((32, 32));
+    // red top left corner, blue edges and center
+    let file = write_sheet("radiant_sliced_sprite", r#"{
+        "image": "IMAGE",
+        "frames": [ { "x": 0, "y": 0, "w": 8, "h": 8, "slice": [ 4, 4, 0, 0 ] } ]
+    }"#);
+    let sprite = Sprite::from_sheet_descriptor(target.context(), &file).unwrap();
+    assert_eq!(sprite.frame_slice(0), Some((4., 4., 0., 0.)));
+
+    let layer = Layer::new((32., 32.));
+    sprite.draw_sliced(&layer, 0, ((0., 0.), (24., 16.))).draw();
+    assert_eq!(layer.len(), 4);
+    // too small for the insets, borders are scaled down
+    sprite.draw_sliced(&layer, 0, ((0., 20.), (2., 2.))).draw();
+
+    let pixels = target.render_layer(&layer);
+
+    assert_eq!(pixels.get_pixel(2, 2).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(12, 2).data, [ 0, 0, 255, 255 ]);
+    assert_eq!(pixels.get_pixel(2, 12).data, [ 0, 0, 255, 255 ]);
+    assert_eq!(pixels.get_pixel(20, 12).data, [ 0, 0, 255, 255 ]);
+    assert_eq!(pixels.get_pixel(26, 2).data, [ 0, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(1, 21).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(3, 21).data, [ 0, 0, 0, 255 ]);
+
+    // tiling repeats the 4x4 edges and center
+    let tiled = Layer::new((32., 32.));
+    sprite.draw_sliced(&tiled, 0, ((0., 0.), (24., 16.))).tile().draw();
+    assert_eq!(tiled.len(), 1 + 5 + 3 + 5 * 3);
+    // explicit insets override the descriptor
+    let stretched = Layer::new((32., 32.));
+    sprite.draw_sliced(&stretched, 0, ((0., 0.), (24., 16.))).insets((2., 2., 2., 2.)).draw();
+    assert_eq!(stretched.len(), 9);
+    // parts narrower than a pixel are tiled at one pixel, excessive tile counts fall back to stretching
+    let narrow = Layer::new((32., 32.));
+    sprite.draw_sliced(&narrow, 0, ((0., 0.), (24., 16.))).insets((3.95, 3.95, 3.95, 3.95)).tile().draw();
+    assert_eq!(narrow.len(), 4 + 17 * 2 + 9 * 2 + 17 * 9);
+    let wide = Layer::new((32., 32.));
+    sprite.draw_sliced(&wide, 0, ((0., 0.), (100000., 8.))).tile().draw();
+    assert_eq!(wide.len(), 4);
+}
+
+#[test]
+fn tilemap() {
+    let target = TestDisplay::new((64, 32));
+    let file = write_sheet("radiant_tilemap", r#"{
+        "image": "IMAGE",
+        "frames": [ { "x": 0, "y": 0, "w": 4, "h": 4 }, { "x": 4, "y": 0, "w": 4, "h": 4 } ]
+    }"#);
+    let sprite = Sprite::from_sheet_descriptor(target.context(), &file).unwrap();
+
+    // spans three chunks horizontally, the last one is outside of the view
+    let mut map = TileMap::new(&sprite, (40, 4), (64., 32.));
+    assert_eq!(map.tile_size(), (4., 4.));
+    map.set_tile(0, 0, Some(0)).set_tile(2, 1, Some(1)).set_tile(39, 3, Some(1));
+    assert_eq!(map.tile(2, 1), Some(1));
+    assert_eq!(map.tile(3, 1), None);
+
+    let pixels = target.render(|renderer| { renderer.draw_tilemap(&map, 0); });
+    assert_eq!(pixels.get_pixel(1, 1).data, [ 255, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(9, 5).data, [ 0, 0, 255, 255 ]);
+    assert_eq!(pixels.get_pixel(13, 5).data, [ 0, 0, 0, 255 ]);
+
+    // changed tiles are redrawn
+    map.set_tile(0, 0, None);
+    let pixels = target.render(|renderer| { renderer.draw_tilemap(&map, 0); });
+    assert_eq!(pixels.get_pixel(1, 1).data, [ 0, 0, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(9, 5).data, [ 0, 0, 255, 255 ]);
+
+    map.set_orientation(TileOrientation::Isometric).set_tile_size((64., 36.));
+    assert_eq!(map.tile_position(0, 0), (0., 18.));
+    assert_eq!(map.tile_position(1, 0), (32., 36.));
+    assert_eq!(map.tile_position(0, 1), (-32., 36.));
+    map.set_orientation(TileOrientation::Hexagonal).set_tile_size((8., 8.));
+    assert_eq!(map.tile_position(0, 0), (4., 4.));
+    assert_eq!(map.tile_position(0, 1), (8., 10.));
+}
+
+#[cfg(feature = "tmx")]
+#[test]
+fn tilemap_tmx() {
+    let target = TestDisplay::new((32, 32));
+    let file = write_sheet("radiant_tilemap_tmx", r#"{
+        "image": "IMAGE",
+        "frames": [ { "x": 0, "y": 0, "w": 4, "h": 4 }, { "x": 4, "y": 0, "w": 4, "h": 4 }, { "name": "blue.png", "x": 8, "y": 0, "w": 4, "h": 4 } ]
+    }"#);
+    let sprite = Sprite::from_sheet_descriptor(target.context(), &file).unwrap();
+    let load = |tilesets: &str, data: &str| {
+        let file = std::env::temp_dir().join("radiant_tilemap_tmx.tmx");
+        std::fs::write(&file, format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+            <map version="1.0" orientation="orthogonal" width="3" height="1" tilewidth="4" tileheight="4">
+                {}
+                <layer name="ground" width="3" height="1"><data encoding="csv">{}</data></layer>
+            </map>"#, tilesets, data)).unwrap();
+        TileMap::from_tmx(&sprite, file.to_str().unwrap(), (32., 32.))
+    };
+    let collection = r#"<tileset firstgid="1" name="collection" tilewidth="4" tileheight="4"><tile id="0"><image width="4" height="4" source="tiles/blue.png"/></tile></tileset>"#;
+    let sheet = r#"<tileset firstgid="2" name="sheet" tilewidth="4" tileheight="4"><image source="sheet.png" width="8" height="4"/></tileset>"#;
+    let other_sheet = r#"<tileset firstgid="4" name="other" tilewidth="4" tileheight="4"><image source="other.png" width="4" height="4"/></tileset>"#;
+
+    // named tiles map to frames of the same name, sheet tiles to frames by index
+    let maps = load(&format!("{}{}", collection, sheet), "1,2,3").unwrap();
+    assert_eq!(maps.len(), 1);
+    assert_eq!((maps[0].tile(0, 0), maps[0].tile(1, 0), maps[0].tile(2, 0)), (Some(2), Some(0), Some(1)));
+
+    // the sheet only covers two tiles even though the sprite has three frames
+    match load(&format!("{}{}", collection, sheet), "1,2,4") {
+        Err(Error::TileMapError(_)) => { }
+        other => panic!("expected TileMapError, got {:?}", other.map(|maps| maps.len())),
+    }
+    match load(&format!("{}{}{}", collection, sheet, other_sheet), "1,2,3") {
+        Err(Error::TileMapError(_)) => { }
+        other => panic!("expected TileMapError, got {:?}", other.map(|maps| maps.len())),
+    }
+}
+
+#[test]
+fn layer_sort_modes() {
+    let target = TestDisplay::new((32, 32));
+    let red = solid_sprite(target.context(), (8, 8), [ 255, 0, 0, 255 ]);
+    let blue = solid_sprite(target.context(), (8, 8), [ 0, 0, 255, 255 ]);
+
+    let layer = Layer::new((32., 32.));
+    assert_eq!(layer.sort_mode(), LayerSortMode::Insertion);
+    red.draw_builder(&layer, 0).position((8., 9.)).z(-1.0).draw();
+    blue.draw_builder(&layer, 0).position((8., 8.)).draw();
+    red.draw(&layer, 0, (24., 10.), Color::WHITE);
+    blue.draw(&layer, 0, (24., 8.), Color::WHITE);
+
+    let render = |sort_mode| {
+        layer.set_sort_mode(sort_mode);
+        let pixels = target.render_layer(&layer);
+        (pixels.get_pixel(8, 8).data, pixels.get_pixel(24, 9).data)
+    };
+
+    assert_eq!(render(LayerSortMode::Insertion), ([ 0, 0, 255, 255 ], [ 0, 0, 255, 255 ]));
+    // equal z values are ordered by vertical position
+    assert_eq!(render(LayerSortMode::Z), ([ 0, 0, 255, 255 ], [ 255, 0, 0, 255 ]));
+    assert_eq!(render(LayerSortMode::Y), ([ 255, 0, 0, 255 ], [ 255, 0, 0, 255 ]));
+
+    // draw calls are sorted as a unit, keeping the order of their quads
+    let square = |size: f32, color: Color| vec![
+        MeshVertex::new((-size, -size), (0., 0.), color),
+        MeshVertex::new((size, -size), (1., 0.), color),
+        MeshVertex::new((-size, size), (0., 1.), color),
+        MeshVertex::new((size, size), (1., 1.), color),
+    ];
+    let mut vertices = square(2., Color::RED);
+    vertices.extend(square(6., Color::GREEN));
+    let layer = Layer::new((32., 32.));
+    layer.set_sort_mode(LayerSortMode::Z);
+    layer.draw_mesh((8., 24.), &vertices, &[ 0, 1, 2, 1, 3, 2, 4, 5, 6, 5, 7, 6 ], 0.);
+    layer.fill_rect(((16., 16.), (16., 16.)), Color::BLUE, 1.);
+    layer.stroke_rect(((16., 16.), (16., 16.)), 4., Color::RED, 0.);
+    let pixels = target.render_layer(&layer);
+    assert_eq!(pixels.get_pixel(8, 24).data, [ 0, 255, 0, 255 ]);
+    assert_eq!(pixels.get_pixel(17, 17).data, [ 0, 0, 255, 255 ]);
+}
+
+#[test]
+fn retained_layer() {
+    let target = TestDisplay::new((32, 32));
+    let red = solid_sprite(target.context(), (8, 8), [ 255, 0, 0, 255 ]);
+    let blue = solid_sprite(target.context(), (8, 8), [ 0, 0, 255, 255 ]);
+
+    let layer = Layer::retained((32., 32.));
+    assert!(layer.is_retained());
+    let first = layer.add_sprite(&red, 0, (8., 8.), Color::WHITE);
+    let second = layer.add_sprite(&blue, 0, (24., 8.), Color::WHITE);
+    let third = layer.add_sprite(&red, 0, (8., 24.), Color::WHITE);
+    // immediate draws are kept until the layer is cleared
+    blue.draw(&layer, 0, (24., 24.), Color::WHITE);
+
+    let render = || {
+        let pixels = target.render_layer(&layer);
+        [ (8, 8), (24, 8), (8, 24), (24, 24) ].iter().map(|&(x, y)| pixels.get_pixel(x, y).data).collect::<Vec<_>>()
+    };
+
+    let (black, red_pixel, blue_pixel) = ([ 0, 0, 0, 255 ], [ 255, 0, 0, 255 ], [ 0, 0, 255, 255 ]);
+    assert_eq!(render(), vec![ red_pixel, blue_pixel, red_pixel, blue_pixel ]);
+    assert_eq!(render(), vec![ red_pixel, blue_pixel, red_pixel, blue_pixel ]);
+
+    layer.set_sprite_position(first, (24., 8.)).set_sprite_visible(third, false);
+    assert_eq!(render(), vec![ black, blue_pixel, black, blue_pixel ]);
+
+    layer.remove_sprite(second);
+    assert!(!layer.contains_sprite(second));
+    assert!(layer.contains_sprite(first));
+    let len = layer.len();
+    let reused = layer.add_sprite(&blue, 0, (8., 8.), Color::WHITE);
+    assert_eq!(layer.len(), len);
+    assert!(!layer.contains_sprite(second));
+    layer.set_sprite_visible(third, true).set_sprite_color(reused, Color::BLACK);
+    assert_eq!(render(), vec![ black, red_pixel, red_pixel, blue_pixel ]);
+
+    layer.clear();
+    assert!(!layer.contains_sprite(first));
+    assert_eq!(render(), vec![ black, black, black, black ]);
+}
+
+#[test]
+fn layer_culling() {
+    let target = TestDisplay::new((32, 32));
+    let red = solid_sprite(target.context(), (8, 8), [ 255, 0, 0, 255 ]);
+
+    let layer = Layer::new((32., 32.));
+    assert!(!layer.culling());
+    layer.set_culling(true);
+    // partially visible, entirely outside and visible only after rotation
+    red.draw(&layer, 0, (-2., 16.), Color::WHITE);
+    red.draw(&layer, 0, (100., 16.), Color::WHITE);
+    red.draw_builder(&layer, 0).position((44., 16.)).scale((1., 4.)).rotation(std::f32::consts::PI / 2.).draw();
+
+    let render = |layer: &Layer| {
+        let pixels = target.render_layer(layer);
+        [ (1, 16), (30, 16), (16, 16) ].iter().map(|&(x, y)| pixels.get_pixel(x, y).data).collect::<Vec<_>>()
+    };
+
+    let (black, red_pixel) = ([ 0, 0, 0, 255 ], [ 255, 0, 0, 255 ]);
+    assert_eq!(render(&layer), vec![ red_pixel, red_pixel, black ]);
+
+    // clones cull against their own matrices
+    let scrolled = layer.clone();
+    {
+        let mut view_matrix = scrolled.view_matrix();
+        view_matrix[3][0] -= 84. * view_matrix[0][0];
+    }
+    assert!(scrolled.culling());
+    assert_eq!(render(&scrolled), vec![ black, black, red_pixel ]);
+    assert_eq!(render(&layer), vec![ red_pixel, red_pixel, black ]);
+}
+
+#[test]
+fn text_measurement() {
+    let display = Display::builder().dimensions((32, 32)).hidden().build().unwrap();
+    let font = test_font(display.context(), 16.0);
+
+    let single = font.measure("Hello", 0.0);
+    assert_eq!(single.line_count, 1);
+    assert_eq!(single.bounds.0, (0.0, 0.0));
+    assert_eq!((single.bounds.1).0, single.line_widths[0]);
+    assert!((single.bounds.1).0 > 0.0 && (single.bounds.1).1 > 0.0);
+
+    let multi = font.measure("Hello\nHello World", 0.0);
+    assert_eq!(multi.line_count, 2);
+    assert_eq!(multi.line_widths[0], single.line_widths[0]);
+    assert!(multi.line_widths[1] > multi.line_widths[0]);
+    assert_eq!((multi.bounds.1).0, multi.line_widths[1]);
+    assert!(((multi.bounds.1).1 - (single.bounds.1).1 - multi.line_height).abs() < 0.001);
+
+    // wrapping produces the same lines as write_wrapped
+    let wrapped = font.measure("Hello Hello Hello Hello", (single.bounds.1).0 * 2.0);
+    assert!(wrapped.line_count > 1);
+    assert_eq!(wrapped.line_widths.len(), wrapped.line_count);
+
+    let glyphs = font.measure_glyphs("Hi\nHo", 0.0);
+    assert_eq!(glyphs.iter().map(|glyph| (glyph.character, glyph.line)).collect::<Vec<_>>(), vec![ ('H', 0), ('i', 0), ('H', 1), ('o', 1) ]);
+    assert_eq!(glyphs[0].rect.0, (0.0, 0.0));
+    assert_eq!((glyphs[2].rect.0).0, 0.0);
+    assert!(((glyphs[2].rect.0).1 - multi.line_height).abs() < 0.001);
+    assert!((glyphs[1].rect.0).0 >= (glyphs[0].rect.1).0 - 1.0);
+
+    // sizes share the parsed font and its cached metrics, which scale linearly
+    let double = font.clone_with_size(32.0).measure("Hello", 0.0);
+    assert!(((double.bounds.1).0 - (single.bounds.1).0 * 2.0).abs() < 0.01);
+    assert_eq!(font.measure("Hello", 0.0), single);
+}
+
+#[test]
+fn text_alignment() {
+    let target = TestDisplay::new((64, 64));
+    let font = test_font(target.context(), 16.0);
+    let layer = Layer::new((64., 64.));
+
+    // returns the bounding box of all lit pixels
+    let render = |align, valign| {
+        layer.clear();
+        font.write_builder(&layer, "HH").position((32., 32.)).align(align).valign(valign).write();
+        let pixels = target.render_layer(&layer);
+        let lit: Vec<(u32, u32)> = pixels.enumerate_pixels().filter(|&(_, _, pixel)| pixel.data[0] > 128).map(|(x, y, _)| (x, y)).collect();
+        let (min_x, max_x) = (lit.iter().map(|p| p.0).min().unwrap(), lit.iter().map(|p| p.0).max().unwrap());
+        let (min_y, max_y) = (lit.iter().map(|p| p.1).min().unwrap(), lit.iter().map(|p| p.1).max().unwrap());
+        (min_x as i32, min_y as i32, max_x as i32, max_y as i32)
+    };
+
+    let (left, top, _, _) = render(HorizontalAlign::Left, VerticalAlign::Top);
+    assert!(left >= 32 && left <= 34 && top >= 32);
+    let (left, _, right, _) = render(HorizontalAlign::Center, VerticalAlign::Top);
+    assert!((left + right - 64).abs() <= 3);
+    let (_, _, right, bottom) = render(HorizontalAlign::Right, VerticalAlign::Bottom);
+    assert!(right <= 32 && right >= 29 && bottom <= 32);
+    let (_, _, _, bottom) = render(HorizontalAlign::Left, VerticalAlign::Baseline);
+    assert!((bottom - 31).abs() <= 1);
+    let (_, top, _, bottom) = render(HorizontalAlign::Left, VerticalAlign::Middle);
+    assert!(top < 32 && bottom >= 32);
+}
+
+#[test]
+fn text_line_breaking() {
+    let display = Display::builder().dimensions((32, 32)).hidden().build().unwrap();
+    let font = test_font(display.context(), 16.0);
+    let lines = |text: &str, max_width: f32| {
+        let mut lines = Vec::new();
+        for glyph in font.measure_glyphs(text, max_width) {
+            if lines.len() <= glyph.line {
+                lines.resize(glyph.line + 1, String::new());
+            }
+            lines[glyph.line].push(glyph.character);
+        }
+        lines
+    };
+
+    // words are kept intact, trailing whitespace stays on the wrapped line
+    let hello_w = (font.measure("Hello W", 0.0).bounds.1).0;
+    assert_eq!(lines("Hello World", hello_w), vec![ "Hello ", "World" ]);
+    let wrapped = font.measure("Hello World", hello_w);
+    assert_eq!(wrapped.line_widths[0], (font.measure("Hello", 0.0).bounds.1).0);
+
+    // explicit breaks
+    assert_eq!(font.measure("Hello\r\nWorld\nagain", 0.0).line_count, 3);
+
+    // words longer than a line are broken anywhere
+    let long = lines("Supercalifragilistic", hello_w);
+    assert!(long.len() > 1);
+    assert_eq!(long.concat(), "Supercalifragilistic");
+
+    // soft hyphens are only shown where the line is wrapped
+    let hyphenated = lines("Hello hyphen\u{ad}ation", (font.measure("Hello hyphen-", 0.0).bounds.1).0);
+    assert_eq!(hyphenated, vec![ "Hello hyphen\u{ad}", "ation" ]);
+    assert_eq!(lines("hyphen\u{ad}ation", 0.0), vec![ "hyphenation" ]);
+}
+
+#[test]
+fn rich_text() {
+    let target = TestDisplay::new((64, 32));
+    let font = test_font(target.context(), 16.0);
+    let large = font.clone_with_size(32.0);
+    let layer = Layer::new((64., 32.));
+
+    // spans lay out like the plain text, larger spans increase the line height
+    let text = RichText::from_markup("[color=#f00][[HH[/color]", &font, Color::WHITE, &[]).unwrap();
+    assert_eq!(text.measure(0.0), font.measure("[HH", 0.0));
+    let text = RichText::from_markup("[color=#f00]HH[/color] [color=#0000ff]HH", &font, Color::WHITE, &[]).unwrap();
+    assert!(((text.measure(0.0).bounds.1).0 - (font.measure("HH HH", 0.0).bounds.1).0).abs() < 1.0);
+    let mixed = RichText::from_markup("H[font=large]H[/font]", &font, Color::WHITE, &[ ("large", &large) ]).unwrap();
+    assert!((mixed.measure(0.0).bounds.1).1 > (font.measure("HH", 0.0).bounds.1).1);
+    let sized = RichText::from_markup("H[size=32]H[/size]", &font, Color::WHITE, &[]).unwrap();
+    assert_eq!(sized.measure(0.0), mixed.measure(0.0));
+
+    assert!(RichText::from_markup("[color=red]H", &font, Color::WHITE, &[]).is_err());
+    assert!(RichText::from_markup("[font=bold]H", &font, Color::WHITE, &[]).is_err());
+    assert!(RichText::from_markup("H[/size]", &font, Color::WHITE, &[]).is_err());
+    assert!(RichText::from_markup("[size=16", &font, Color::WHITE, &[]).is_err());
+
+    // each span is written in its own color
+    let text = RichText::from_markup("[color=#f00]HH[/color][color=#00f]HH[/color]", &font, Color::WHITE, &[]).unwrap();
+    text.write(&layer, (0., 0.));
+    let pixels = target.render_layer(&layer);
+    let red: Vec<u32> = pixels.enumerate_pixels().filter(|&(_, _, pixel)| pixel.data[0] > 128).map(|(x, _, _)| x).collect();
+    let blue: Vec<u32> = pixels.enumerate_pixels().filter(|&(_, _, pixel)| pixel.data[2] > 128).map(|(x, _, _)| x).collect();
+    assert!(red.len() > 0 && blue.len() > 0);
+    assert!(red.iter().max() < blue.iter().min());
+    assert!(pixels.enumerate_pixels().all(|(_, _, pixel)| pixel.data[0] <= 128 || pixel.data[2] <= 128));
+}